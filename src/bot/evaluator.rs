@@ -14,6 +14,30 @@ pub struct EvaluationWeights {
     pub landing_height_weight: f64,
     /// Weight for well structures (columns with deep gaps)
     pub well_weight: f64,
+    /// Weight for overhangs (filled cells with empty cells below them)
+    pub overhang_weight: f64,
+    /// Weight for filled cells in the designated well column (see `keep_well_column`)
+    pub well_column_weight: f64,
+    /// Weight for the depth of the single deepest well
+    pub max_well_depth_weight: f64,
+    /// Bonus for placements that leave the board completely empty
+    pub perfect_clear_weight: f64,
+    /// Weight for piece dependencies beyond the first (spots only specific pieces fill cleanly)
+    pub dependency_weight: f64,
+    /// Weight for finished T-spin triple setups (set positive for T-spin-seeking play)
+    pub tst_setup_weight: f64,
+    /// Weight for holes removed by a placement, only applied by `evaluate_placement`
+    /// (set positive for downstacking)
+    pub hole_reduction_weight: f64,
+    /// Weight for placements other than Tetrises that fill or move the well column of the
+    /// board before the placement, only applied by `evaluate_placement`
+    pub well_shift_weight: f64,
+    /// Weight for the total number of filled cells covering each hole, so deeply
+    /// buried holes count for more than shallow ones
+    pub covered_depth_weight: f64,
+    /// Weight for the drop in `covered_depth` from a placement, only applied by
+    /// `evaluate_placement` (set positive to dig out buried holes)
+    pub dig_weight: f64,
 }
 
 impl Default for EvaluationWeights {
@@ -25,8 +49,18 @@ impl Default for EvaluationWeights {
             complete_lines_weight: 0.760666,
             holes_weight: -0.35663,
             bumpiness_weight: -0.184483,
-            landing_height_weight: -0.0,
+            landing_height_weight: -0.2,
             well_weight: 0.3,
+            overhang_weight: -0.1,
+            well_column_weight: -5.0,
+            max_well_depth_weight: -0.1,
+            perfect_clear_weight: 10.0,
+            dependency_weight: -0.5,
+            tst_setup_weight: 0.0,
+            hole_reduction_weight: 0.0,
+            well_shift_weight: -5.0,
+            covered_depth_weight: -0.1,
+            dig_weight: 0.0,
         }
     }
 }
@@ -34,6 +68,10 @@ impl Default for EvaluationWeights {
 /// Evaluates the quality of a Tetris board state
 pub struct BoardEvaluator {
     weights: EvaluationWeights,
+    /// Column to keep open as a Tetris well; filling it is penalized unless lines clear it
+    pub keep_well_column: Option<usize>,
+    /// Hard cap on the stack height; boards with any column taller score negative infinity
+    pub max_height_limit: Option<usize>,
 }
 
 impl BoardEvaluator {
@@ -41,12 +79,18 @@ impl BoardEvaluator {
     pub fn new() -> Self {
         BoardEvaluator {
             weights: EvaluationWeights::default(),
+            keep_well_column: None,
+            max_height_limit: None,
         }
     }
 
     /// Create a new board evaluator with custom weights
     pub fn with_weights(weights: EvaluationWeights) -> Self {
-        BoardEvaluator { weights }
+        BoardEvaluator {
+            weights,
+            keep_well_column: None,
+            max_height_limit: None,
+        }
     }
 
     /// Main evaluation function - scores a game state based on multiple factors
@@ -55,18 +99,80 @@ impl BoardEvaluator {
         
         // Calculate various metrics that define the board's "quality"
         let column_heights = self.get_column_heights(board);
+        
+        // Over the cap, the placement is only chosen if every other one is too
+        let max_height = column_heights.iter().copied().max().unwrap_or(0) as usize;
+        if self.max_height_limit.is_some_and(|limit| max_height > limit) {
+            return f64::NEG_INFINITY;
+        }
+        
         let aggregate_height = column_heights.iter().sum::<u32>() as f64;
         let holes = self.count_holes(board, &column_heights);
         let complete_lines = self.count_complete_lines(board) as f64;
         let bumpiness = self.calculate_bumpiness(&column_heights);
         let wells = self.calculate_wells(&column_heights);
+        let overhangs = board.count_overhangs() as f64;
+        let well_column_cells = self.count_well_column_cells(board) as f64;
+        let max_well_depth = self.max_well_depth(&column_heights) as f64;
+        let perfect_clear = if board.is_perfect_clear() { 1.0 } else { 0.0 };
+        // A single dependency is normal (e.g. a Tetris well), only extra ones are fragile
+        let excess_dependencies = self.count_dependencies(&column_heights).saturating_sub(1) as f64;
+        let tst_setups = self.tst_setup_count(board) as f64;
+        let covered_depth = self.covered_depth(board) as f64;
+        let landing_height = game.last_landing_height().unwrap_or(0) as f64;
         
         // Apply weights to each metric and get the final score
         (self.weights.aggregate_height_weight * aggregate_height) +
         (self.weights.holes_weight * holes as f64) + 
         (self.weights.complete_lines_weight * complete_lines) +
         (self.weights.bumpiness_weight * bumpiness) + 
-        (self.weights.well_weight * wells)
+        (self.weights.well_weight * wells) +
+        (self.weights.overhang_weight * overhangs) +
+        (self.weights.well_column_weight * well_column_cells) +
+        (self.weights.max_well_depth_weight * max_well_depth) +
+        (self.weights.perfect_clear_weight * perfect_clear) +
+        (self.weights.dependency_weight * excess_dependencies) +
+        (self.weights.tst_setup_weight * tst_setups) +
+        (self.weights.covered_depth_weight * covered_depth) +
+        (self.weights.landing_height_weight * landing_height)
+    }
+
+    /// Score the board a placement leaves, crediting the holes it removed and the cells it
+    /// dug off buried holes compared to the board before the placement (e.g. a clear that
+    /// uncovers buried holes)
+    /// The well established by earlier moves is the well column of the board before the placement
+    pub fn evaluate_placement(&self, before: &Game, after: &Game) -> f64 {
+        let holes_removed = self.hole_count(&before.board) as f64 - self.hole_count(&after.board) as f64;
+        let cover_removed = self.covered_depth(&before.board) as f64 - self.covered_depth(&after.board) as f64;
+        
+        // Clearing a Tetris out of the well is what it's for, so only other placements count
+        let tetris = after.score_system.lines_cleared >= before.score_system.lines_cleared + 4;
+        let well_shifted = match self.well_column(&before.board) {
+            Some(col) if !tetris => self.well_column(&after.board) != Some(col),
+            _ => false,
+        };
+        
+        self.evaluate(after) +
+        (self.weights.hole_reduction_weight * holes_removed) +
+        (self.weights.dig_weight * cover_removed) +
+        (self.weights.well_shift_weight * if well_shifted { 1.0 } else { 0.0 })
+    }
+
+    /// The well column of a board: the one column strictly lower than every other column
+    pub fn well_column(&self, board: &Board) -> Option<usize> {
+        let column_heights = self.get_column_heights(board);
+        let lowest = *column_heights.iter().min()?;
+        
+        let mut lowest_columns = (0..BOARD_WIDTH).filter(|&col| column_heights[col] == lowest);
+        match (lowest_columns.next(), lowest_columns.next()) {
+            (Some(col), None) => Some(col),
+            _ => None,
+        }
+    }
+
+    /// Number of holes on the board
+    fn hole_count(&self, board: &Board) -> u32 {
+        self.count_holes(board, &self.get_column_heights(board))
     }
 
     /// Get the height of each column in the board
@@ -74,12 +180,9 @@ impl BoardEvaluator {
         let mut heights = vec![0; BOARD_WIDTH];
         
         for col in 0..BOARD_WIDTH {
-            for row in 0..BOARD_HEIGHT {
-                if let Some(Cell::Filled(_)) = board.get_cell(row, col) {
-                    // Record this column's height from the top
-                    heights[col] = (BOARD_HEIGHT - row) as u32;
-                    break;
-                }
+            // Record this column's height from its topmost filled cell
+            if let Some(row) = board.column(col).position(|cell| cell.is_filled()) {
+                heights[col] = (BOARD_HEIGHT - row) as u32;
             }
         }
         
@@ -95,36 +198,29 @@ impl BoardEvaluator {
             let top_row = BOARD_HEIGHT - col_height;
             
             // Check for holes below the top block in this column
-            for row in top_row + 1..BOARD_HEIGHT {
-                if let Some(Cell::Empty) = board.get_cell(row, col) {
-                    holes += 1;
-                }
-            }
+            holes += board.column(col)
+                .skip(top_row + 1)
+                .filter(|&&cell| cell == Cell::Empty)
+                .count() as u32;
         }
         
         holes
     }
 
+    /// Total filled cells above each hole in its column, summed over all holes
+    pub fn covered_depth(&self, board: &Board) -> u32 {
+        board.hole_depth_histogram()
+            .iter()
+            .enumerate()
+            .map(|(depth, &holes)| (depth * holes) as u32)
+            .sum()
+    }
+
     /// Count the number of complete lines in the board
     fn count_complete_lines(&self, board: &Board) -> u32 {
-        let mut complete_lines = 0;
-        
-        for row in 0..BOARD_HEIGHT {
-            let mut line_complete = true;
-            
-            for col in 0..BOARD_WIDTH {
-                if let Some(Cell::Empty) = board.get_cell(row, col) {
-                    line_complete = false;
-                    break;
-                }
-            }
-            
-            if line_complete {
-                complete_lines += 1;
-            }
-        }
-        
-        complete_lines
+        (0..BOARD_HEIGHT)
+            .filter(|&row| board.is_line_complete(row))
+            .count() as u32
     }
 
     /// Calculate the bumpiness (sum of differences between adjacent columns)
@@ -159,4 +255,360 @@ impl BoardEvaluator {
         
         well_sum
     }
-}
\ No newline at end of file
+
+    /// Depth of the deepest well on the board
+    pub fn deepest_well_depth(&self, board: &Board) -> u32 {
+        self.max_well_depth(&self.get_column_heights(board))
+    }
+
+    /// Find the deepest well, measured against the lower of the two neighbors
+    /// Unlike `calculate_wells`, the walls count as full-height neighbors so edge wells are included
+    fn max_well_depth(&self, column_heights: &[u32]) -> u32 {
+        let mut deepest = 0;
+        
+        for i in 0..column_heights.len() {
+            let left_height = if i > 0 { column_heights[i - 1] } else { BOARD_HEIGHT as u32 };
+            let right_height = if i < column_heights.len() - 1 { column_heights[i + 1] } else { BOARD_HEIGHT as u32 };
+            
+            let depth = std::cmp::min(left_height, right_height).saturating_sub(column_heights[i]);
+            deepest = deepest.max(depth);
+        }
+        
+        deepest
+    }
+
+    /// Number of piece dependencies on the board
+    pub fn dependency_count(&self, board: &Board) -> u32 {
+        self.count_dependencies(&self.get_column_heights(board))
+    }
+
+    /// Count spots that only specific pieces fill cleanly: 1-wide slots at least two deep
+    /// (I, or J/L against the taller side) and 2-wide notches with a one-cell step (S/Z, J/L)
+    /// The walls count as full-height neighbors
+    fn count_dependencies(&self, column_heights: &[u32]) -> u32 {
+        let height_at = |i: usize| column_heights.get(i).copied().unwrap_or(BOARD_HEIGHT as u32);
+        let mut dependencies = 0;
+        
+        for i in 0..column_heights.len() {
+            let height = column_heights[i];
+            let left_height = if i > 0 { column_heights[i - 1] } else { BOARD_HEIGHT as u32 };
+            
+            // 1-wide slot
+            if left_height >= height + 2 && height_at(i + 1) >= height + 2 {
+                dependencies += 1;
+            }
+            
+            // 2-wide notch whose floor steps by one cell
+            if let Some(&next_height) = column_heights.get(i + 1) {
+                let floor = height.max(next_height);
+                if height.abs_diff(next_height) == 1 && left_height >= floor + 2 && height_at(i + 2) >= floor + 2 {
+                    dependencies += 1;
+                }
+            }
+        }
+        
+        dependencies
+    }
+
+    /// Count T-spin triple setups ready to fire: a vertical T slot with its side nub
+    /// covered by an overhang, where a T piece would complete all three rows
+    pub fn tst_setup_count(&self, board: &Board) -> u32 {
+        let mut setups = 0;
+        
+        for row in 2..BOARD_HEIGHT as i32 - 1 {
+            // Each of the three rows must be complete apart from the slot
+            if board.filled_count_in_row(row as usize - 1) != BOARD_WIDTH - 1 ||
+                board.filled_count_in_row(row as usize) != BOARD_WIDTH - 2 ||
+                board.filled_count_in_row(row as usize + 1) != BOARD_WIDTH - 1 {
+                continue;
+            }
+            
+            for col in 0..BOARD_WIDTH as i32 {
+                // The nub of the T can point either way
+                for side in [-1, 1] {
+                    let slot = [(row - 1, col), (row, col), (row + 1, col), (row, col + side)];
+                    let walls = [
+                        (row - 1, col + side), // Overhang above the nub
+                        (row + 1, col + side),
+                        (row - 1, col - side),
+                        (row, col - side),
+                        (row + 1, col - side),
+                        (row + 2, col),
+                    ];
+                    
+                    // The slot has to be open from above for the T to get in
+                    let open_above = !board.is_filled_or_wall(row - 2, col, true);
+                    if open_above &&
+                        slot.iter().all(|&(r, c)| !board.is_filled_or_wall(r, c, true)) &&
+                        walls.iter().all(|&(r, c)| board.is_filled_or_wall(r, c, true)) {
+                        setups += 1;
+                    }
+                }
+            }
+        }
+        
+        setups
+    }
+
+    /// Count filled cells in the designated well column (zero if no well is kept)
+    /// Lines are already cleared on the evaluated board, so a clear that used the well isn't penalized
+    fn count_well_column_cells(&self, board: &Board) -> u32 {
+        let col = match self.keep_well_column {
+            Some(col) if col < BOARD_WIDTH => col,
+            _ => return 0,
+        };
+        
+        board.column(col).filter(|cell| cell.is_filled()).count() as u32
+    }
+}
+
+/// Shaped reward for the transition between two consecutive boards (for RL setups)
+/// Combines the change in aggregate height and holes with the lines cleared,
+/// weighted with the default evaluation weights
+pub fn board_delta_reward(before: &Board, after: &Board, lines_cleared: usize) -> f64 {
+    let evaluator = BoardEvaluator::new();
+    let weights = &evaluator.weights;
+    
+    let heights_before = evaluator.get_column_heights(before);
+    let heights_after = evaluator.get_column_heights(after);
+    let height_delta = heights_after.iter().sum::<u32>() as f64 - heights_before.iter().sum::<u32>() as f64;
+    let holes_delta = evaluator.count_holes(after, &heights_after) as f64 -
+        evaluator.count_holes(before, &heights_before) as f64;
+    
+    (weights.aggregate_height_weight * height_delta) +
+    (weights.holes_weight * holes_delta) +
+    (weights.complete_lines_weight * lines_cleared as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tetris_core::PieceType;
+
+    #[test]
+    fn test_board_delta_reward_prefers_clears() {
+        // Bottom row missing a single cell
+        let mut before = Board::new();
+        for col in 1..BOARD_WIDTH {
+            before.set_cell(BOARD_HEIGHT - 1, col, Cell::Filled(PieceType::O));
+        }
+        
+        // Filling the gap clears the row and empties the board
+        let cleared = Board::new();
+        
+        // Stacking a block on top instead keeps the row
+        let mut stacked = before.clone();
+        stacked.set_cell(BOARD_HEIGHT - 2, 5, Cell::Filled(PieceType::O));
+        
+        let clear_reward = board_delta_reward(&before, &cleared, 1);
+        let stack_reward = board_delta_reward(&before, &stacked, 0);
+        assert!(clear_reward > stack_reward);
+        assert!(clear_reward > 0.0);
+    }
+
+    #[test]
+    fn test_max_height_limit() {
+        let mut evaluator = BoardEvaluator::new();
+        evaluator.max_height_limit = Some(4);
+        
+        // A single column stacked to height 4 is right at the cap
+        let mut game = Game::new_empty();
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+            game.board.set_cell(row, 0, Cell::Filled(PieceType::O));
+        }
+        assert!(evaluator.evaluate(&game).is_finite());
+        
+        // One more cell puts it over
+        game.board.set_cell(BOARD_HEIGHT - 5, 0, Cell::Filled(PieceType::O));
+        assert_eq!(evaluator.evaluate(&game), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_hole_reduction_weight() {
+        let evaluator = BoardEvaluator::with_weights(EvaluationWeights {
+            hole_reduction_weight: 5.0,
+            ..Default::default()
+        });
+        
+        // Two rows of cheese: the bottom gap is covered, the row above is open on the right
+        let mut before = Game::new();
+        for col in 1..BOARD_WIDTH {
+            before.board.set_cell(BOARD_HEIGHT - 1, col, Cell::Garbage);
+        }
+        for col in 0..BOARD_WIDTH - 2 {
+            before.board.set_cell(BOARD_HEIGHT - 2, col, Cell::Garbage);
+        }
+        
+        // Placements drop an O piece straight down from the given column
+        before.current_piece = before.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::O;
+            piece.row = 0;
+            piece
+        });
+        let o_piece_at = |col: i32| {
+            let mut game = before.clone();
+            if let Some(ref mut piece) = game.current_piece {
+                piece.col = col;
+            }
+            game.hard_drop();
+            game
+        };
+        
+        // An O on the right clears the upper row and uncovers the hole below it
+        let clear = o_piece_at(BOARD_WIDTH as i32 - 2);
+        assert_eq!(clear.score_system.lines_cleared, 1);
+        
+        // An O on the left sits flat on the stack without clearing anything
+        let clean = o_piece_at(0);
+        assert_eq!(clean.score_system.lines_cleared, 0);
+        
+        assert!((evaluator.evaluate_placement(&before, &clear) - evaluator.evaluate(&clear) - 5.0).abs() < 1e-9);
+        assert!((evaluator.evaluate_placement(&before, &clean) - evaluator.evaluate(&clean)).abs() < 1e-9);
+        assert!(evaluator.evaluate_placement(&before, &clear) > evaluator.evaluate_placement(&before, &clean));
+    }
+
+    #[test]
+    fn test_well_shift_weight() {
+        let shift_weight = EvaluationWeights::default().well_shift_weight;
+        assert!(shift_weight < 0.0);
+        let evaluator = BoardEvaluator::new();
+        let unweighted = BoardEvaluator::with_weights(EvaluationWeights {
+            well_shift_weight: 0.0,
+            ..Default::default()
+        });
+        let shift_penalty = |before: &Game, after: &Game| {
+            evaluator.evaluate_placement(before, after) - unweighted.evaluate_placement(before, after)
+        };
+        
+        // A clean stack four rows tall with the well on the right
+        let mut before = Game::new();
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH - 1 {
+                before.board.set_cell(row, col, Cell::Filled(PieceType::O));
+            }
+        }
+        assert_eq!(evaluator.well_column(&before.board), Some(BOARD_WIDTH - 1));
+        
+        // Moving the well to the left edge costs the default weight
+        let mut moved = before.clone();
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+            moved.board.set_cell(row, 0, Cell::Empty);
+            moved.board.set_cell(row, BOARD_WIDTH - 1, Cell::Filled(PieceType::I));
+        }
+        assert_eq!(evaluator.well_column(&moved.board), Some(0));
+        assert!((shift_penalty(&before, &moved) - shift_weight).abs() < 1e-9);
+        
+        // Stacking beside the well costs nothing extra
+        let mut kept = before.clone();
+        kept.board.set_cell(BOARD_HEIGHT - 5, 0, Cell::Filled(PieceType::O));
+        assert!(shift_penalty(&before, &kept).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_covered_depth() {
+        let evaluator = BoardEvaluator::new();
+        assert_eq!(evaluator.covered_depth(&Board::new()), 0);
+        
+        // A hole at the bottom of column 2 under one cell, and one in column 6 under six
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 2, 2, Cell::Filled(PieceType::O));
+        for row in BOARD_HEIGHT - 7..BOARD_HEIGHT - 1 {
+            board.set_cell(row, 6, Cell::Filled(PieceType::I));
+        }
+        assert_eq!(evaluator.covered_depth(&board), 7);
+        
+        // Each covering cell costs the weight once
+        let weighted = BoardEvaluator::with_weights(EvaluationWeights {
+            covered_depth_weight: -1.0,
+            ..Default::default()
+        });
+        let unweighted = BoardEvaluator::with_weights(EvaluationWeights {
+            covered_depth_weight: 0.0,
+            ..Default::default()
+        });
+        let mut game = Game::new();
+        game.board = board;
+        assert!((unweighted.evaluate(&game) - weighted.evaluate(&game) - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_landing_height_weight() {
+        let weighted = BoardEvaluator::with_weights(EvaluationWeights {
+            landing_height_weight: -1.0,
+            ..Default::default()
+        });
+        let unweighted = BoardEvaluator::with_weights(EvaluationWeights {
+            landing_height_weight: 0.0,
+            ..Default::default()
+        });
+        
+        // Nothing has landed yet, so there's nothing to penalize
+        let mut game = Game::new();
+        assert!((unweighted.evaluate(&game) - weighted.evaluate(&game)).abs() < 1e-9);
+        
+        // An O dropped on the empty floor lands two rows high
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::O;
+            piece
+        });
+        game.hard_drop();
+        assert!((unweighted.evaluate(&game) - weighted.evaluate(&game) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_deepest_well_depth() {
+        let evaluator = BoardEvaluator::new();
+        let mut board = Board::new();
+        
+        // A stack six rows tall with a single open column in the middle
+        for row in BOARD_HEIGHT - 6..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                if col != 4 {
+                    board.set_cell(row, col, Cell::Filled(PieceType::O));
+                }
+            }
+        }
+        assert_eq!(evaluator.deepest_well_depth(&board), 6);
+        
+        // An empty board has no wells
+        assert_eq!(evaluator.deepest_well_depth(&Board::new()), 0);
+    }
+
+    #[test]
+    fn test_dependency_count() {
+        let evaluator = BoardEvaluator::new();
+        assert_eq!(evaluator.dependency_count(&Board::new()), 0);
+        
+        // A slot in column 4 with walls three and two cells tall
+        let mut board = Board::new();
+        for row in BOARD_HEIGHT - 3..BOARD_HEIGHT {
+            board.set_cell(row, 3, Cell::Filled(PieceType::O));
+        }
+        for row in BOARD_HEIGHT - 2..BOARD_HEIGHT {
+            board.set_cell(row, 5, Cell::Filled(PieceType::O));
+        }
+        assert!(evaluator.dependency_count(&board) > 0);
+    }
+
+    #[test]
+    fn test_tst_setup_count() {
+        let evaluator = BoardEvaluator::new();
+        assert_eq!(evaluator.tst_setup_count(&Board::new()), 0);
+        
+        // A slot in column 4 with its nub in column 3 under an overhang
+        let mut board = Board::new();
+        for row in BOARD_HEIGHT - 3..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                let in_slot = col == 4 || (row == BOARD_HEIGHT - 2 && col == 3);
+                if !in_slot {
+                    board.set_cell(row, col, Cell::Filled(PieceType::O));
+                }
+            }
+        }
+        assert_eq!(evaluator.tst_setup_count(&board), 1);
+        
+        // Without the overhang it's just a well
+        board.set_cell(BOARD_HEIGHT - 3, 3, Cell::Empty);
+        assert_eq!(evaluator.tst_setup_count(&board), 0);
+    }
+}
@@ -2,8 +2,41 @@ mod evaluator;
 mod move_finder;
 
 use super::tetris_core::{Game, GameState};
-use move_finder::MoveFinder;
-use evaluator::BoardEvaluator;
+
+pub use evaluator::{board_delta_reward, BoardEvaluator};
+pub use move_finder::{Move, MoveFinder};
+
+/// Outcome of letting the bot play a bounded number of placements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayResult {
+    /// Pieces the bot placed
+    pub placements: usize,
+    /// Lines cleared during the run
+    pub lines_cleared: u32,
+    /// Score gained during the run
+    pub score: u32,
+}
+
+/// Pieces a benchmark game may place before it's stopped, since a good bot may never top out
+const BENCHMARK_MAX_PIECES: usize = 200;
+
+/// Pieces a benchmark game has to place to count as survived
+const BENCHMARK_SURVIVAL_PIECES: usize = 100;
+
+/// Aggregate results of `TetrisBot::benchmark`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    /// Games played
+    pub games: usize,
+    /// Mean lines cleared per game
+    pub mean_lines: f64,
+    /// Median lines cleared per game
+    pub median_lines: f64,
+    /// Highest score of any game
+    pub max_score: u32,
+    /// Games that placed at least `BENCHMARK_SURVIVAL_PIECES` pieces
+    pub games_survived: usize,
+}
 
 /// The main bot that plays Tetris
 pub struct TetrisBot {
@@ -20,40 +53,170 @@ impl TetrisBot {
         }
     }
 
-    /// Find and execute the best move for the current game state
-    pub fn make_move(&self, game: &mut Game) -> bool {
-        // Get all possible moves for the current piece
-        let possible_moves = self.move_finder.find_possible_moves(game);
-        
-        if possible_moves.is_empty() {
-            return false; // No moves available
+    /// Create a Tetris bot that scores boards with the given evaluator
+    pub fn with_evaluator(evaluator: BoardEvaluator) -> Self {
+        TetrisBot {
+            evaluator,
+            move_finder: MoveFinder::new(),
+        }
+    }
+
+    /// Create a Tetris bot that searches for moves with the given move finder
+    pub fn with_move_finder(move_finder: MoveFinder) -> Self {
+        TetrisBot {
+            evaluator: BoardEvaluator::new(),
+            move_finder,
+        }
+    }
+
+    /// Find the best move for the current game state without applying it
+    pub fn best_move(&self, game: &Game) -> Option<Move> {
+        if game.state != GameState::Playing {
+            return None;
         }
         
+        // Get all possible moves for the current piece, already simulated on clones
+        let placements = self.move_finder.find_possible_placements(game);
+        
         // Evaluate each move and find the best one
-        let mut best_move = &possible_moves[0];
+        let mut best_move: Option<&Move> = None;
         let mut best_score = f64::NEG_INFINITY;
         
-        for possible_move in &possible_moves {
-            // Clone the game to simulate the move
-            let mut game_clone = game.clone();
-            
-            // Apply the move to the clone
-            self.move_finder.apply_move(&mut game_clone, possible_move);
-            
-            // Evaluate the resulting board
-            let score = self.evaluator.evaluate(&game_clone);
+        for (possible_move, game_clone) in &placements {
+            // Evaluate the resulting board against the one we started from
+            let score = self.evaluator.evaluate_placement(game, game_clone);
             
-            // Update best move if this is better
-            if score > best_score {
+            // Update best move if this is better, or equally good with fewer inputs
+            let is_better = match best_move {
+                None => true,
+                Some(current_best) => score > best_score ||
+                    (score == best_score && possible_move.input_count() < current_best.input_count()),
+            };
+            if is_better {
                 best_score = score;
-                best_move = possible_move;
+                best_move = Some(possible_move);
+            }
+        }
+        
+        // None if no moves are available
+        best_move.cloned()
+    }
+
+    /// The best move together with how far its evaluation is ahead of the runner-up
+    /// Moves that end on the same board as the best move don't count as the runner-up;
+    /// the margin is 0 if there is no other placement
+    pub fn best_move_with_margin(&self, game: &Game) -> Option<(Move, f64)> {
+        let best = self.best_move(game)?;
+        let best_game = self.move_finder.apply_move_cloned(game, &best)?;
+        let best_score = self.evaluator.evaluate_placement(game, &best_game);
+        
+        let runner_up = self.move_finder.find_possible_placements(game)
+            .into_iter()
+            .map(|(_, game_clone)| game_clone)
+            .filter(|game_clone| game_clone.board != best_game.board)
+            .map(|game_clone| self.evaluator.evaluate_placement(game, &game_clone))
+            .reduce(f64::max);
+        
+        Some((best, runner_up.map_or(0.0, |score| best_score - score)))
+    }
+
+    /// Whether holding leads to a better placement than playing the current piece
+    pub fn should_hold(&self, game: &Game) -> bool {
+        if game.state != GameState::Playing || game.piece_after_hold().is_none() {
+            return false;
+        }
+        
+        let mut held_game = game.clone();
+        if !held_game.hold_piece() {
+            return false;
+        }
+        
+        match (self.best_placement_score(game), self.best_placement_score(&held_game)) {
+            (Some(current), Some(after_hold)) => after_hold > current,
+            (None, Some(_)) => true,
+            _ => false,
+        }
+    }
+    
+    /// Best evaluation reachable by placing the current piece, without holding
+    fn best_placement_score(&self, game: &Game) -> Option<f64> {
+        self.move_finder.find_possible_placements(game)
+            .into_iter()
+            .filter(|(possible_move, _)| !possible_move.hold)
+            .map(|(_, game_clone)| self.evaluator.evaluate_placement(game, &game_clone))
+            .reduce(f64::max)
+    }
+
+    /// Find and execute the best move for the current game state
+    pub fn make_move(&self, game: &mut Game) -> bool {
+        // Wait out any entry delay so the next piece is in play
+        if game.is_in_are() {
+            game.update(game.config.are);
+        }
+        
+        match self.best_move(game) {
+            Some(best_move) => {
+                // Apply the best move to the actual game
+                self.move_finder.apply_move(game, &best_move)
+            }
+            // No usable moves, but dropping the piece where it is always locks it
+            None if game.state == GameState::Playing && game.current_piece.is_some() => game.hard_drop(),
+            None => false, // No moves available
+        }
+    }
+    
+    /// Play up to `n` placements, stopping early on game over
+    pub fn play_n(&self, game: &mut Game, n: usize) -> PlayResult {
+        let starting_lines = game.score_system.lines_cleared;
+        let starting_score = game.score_system.score;
+        
+        let mut placements = 0;
+        while placements < n && game.state == GameState::Playing {
+            if !self.make_move(game) {
+                break; // No more moves possible
             }
+            placements += 1;
         }
         
-        // Apply the best move to the actual game
-        self.move_finder.apply_move(game, best_move);
+        PlayResult {
+            placements,
+            lines_cleared: game.score_system.lines_cleared - starting_lines,
+            score: game.score_system.score - starting_score,
+        }
+    }
+    
+    /// Play `games` seeded games (seeds `seed_base`, `seed_base + 1`, ...) until game over or
+    /// `BENCHMARK_MAX_PIECES` pieces, and summarize how the bot did
+    pub fn benchmark(&self, games: usize, seed_base: u64) -> BenchmarkReport {
+        let results: Vec<PlayResult> = (0..games as u64)
+            .map(|index| {
+                let mut game = Game::new_match(seed_base.wrapping_add(index));
+                self.play_n(&mut game, BENCHMARK_MAX_PIECES)
+            })
+            .collect();
+        
+        let mut lines: Vec<u32> = results.iter().map(|result| result.lines_cleared).collect();
+        lines.sort();
+        let mean_lines = if games == 0 {
+            0.0
+        } else {
+            lines.iter().sum::<u32>() as f64 / games as f64
+        };
+        let median_lines = match games {
+            0 => 0.0,
+            _ if games % 2 == 1 => lines[games / 2] as f64,
+            _ => (lines[games / 2 - 1] + lines[games / 2]) as f64 / 2.0,
+        };
         
-        true
+        BenchmarkReport {
+            games,
+            mean_lines,
+            median_lines,
+            max_score: results.iter().map(|result| result.score).max().unwrap_or(0),
+            games_survived: results.iter()
+                .filter(|result| result.placements >= BENCHMARK_SURVIVAL_PIECES)
+                .count(),
+        }
     }
     
     /// Play the game automatically until game over
@@ -69,6 +232,9 @@ impl TetrisBot {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::evaluator::EvaluationWeights;
+    use crate::tetris_core::{Cell, GameConfig, PieceType, ScriptedRandomizer, BOARD_HEIGHT, BOARD_WIDTH};
+    use std::time::Duration;
     
     #[test]
     fn test_bot_can_make_move() {
@@ -77,4 +243,282 @@ mod tests {
         
         assert!(bot.make_move(&mut game));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_best_move_does_not_apply() {
+        let bot = TetrisBot::new();
+        let mut game = Game::new();
+        
+        let suggested = bot.best_move(&game);
+        assert!(suggested.is_some());
+        
+        // Applying the suggestion by hand matches what make_move does
+        let mut suggested_game = game.clone();
+        MoveFinder::new().apply_move(&mut suggested_game, &suggested.unwrap());
+        bot.make_move(&mut game);
+        assert_eq!(suggested_game.board, game.board);
+        
+        // No suggestion once the game is over
+        game.state = GameState::GameOver;
+        assert!(bot.best_move(&game).is_none());
+    }
+    
+    #[test]
+    fn test_keep_well_column() {
+        // Weigh the well heavily enough that no stack shape makes filling it worthwhile
+        let mut evaluator = BoardEvaluator::with_weights(EvaluationWeights {
+            well_column_weight: -100.0,
+            ..Default::default()
+        });
+        evaluator.keep_well_column = Some(BOARD_WIDTH - 1);
+        let bot = TetrisBot::with_evaluator(evaluator);
+        
+        // A mid-game stack with both edge columns open, so the well can't clear lines
+        let mut game = Game::new();
+        for row in BOARD_HEIGHT - 3..BOARD_HEIGHT {
+            for col in 1..BOARD_WIDTH - 1 {
+                game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+            }
+        }
+        game.can_hold = false;
+        
+        for _ in 0..3 {
+            assert!(bot.make_move(&mut game));
+            for row in 0..BOARD_HEIGHT {
+                assert_eq!(game.board.get_cell(row, BOARD_WIDTH - 1), Some(&Cell::Empty));
+            }
+            game.can_hold = false;
+        }
+    }
+    
+    #[test]
+    fn test_prefers_not_holding_on_ties() {
+        let bot = TetrisBot::new();
+        let mut game = Game::new();
+        
+        // The bottom row only needs the two cells under the spawn position
+        for col in 0..BOARD_WIDTH {
+            if col != 4 && col != 5 {
+                game.board.set_cell(BOARD_HEIGHT - 1, col, Cell::Filled(PieceType::O));
+            }
+        }
+        
+        // Holding swaps the O piece for another O, reaching the exact same placement
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::O;
+            piece.row = 0;
+            piece
+        });
+        game.held_piece = Some(PieceType::O);
+        
+        let best = bot.best_move(&game).unwrap();
+        assert!(!best.hold);
+        assert_eq!(best.input_count(), 0);
+    }
+    
+    #[test]
+    fn test_best_move_with_margin() {
+        let bot = TetrisBot::new();
+        let mut game = Game::new();
+        game.can_hold = false;
+        
+        // Four rows waiting on an I piece in the left column: only one placement clears them
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+            for col in 1..BOARD_WIDTH {
+                game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+            }
+        }
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::I;
+            piece
+        });
+        let (best, margin) = bot.best_move_with_margin(&game).unwrap();
+        assert_eq!(Some(best), bot.best_move(&game));
+        assert!(margin > 5.0);
+        
+        // On an empty board an O piece is equally good in every column
+        game.board.clear();
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::O;
+            piece.row = 0;
+            piece
+        });
+        let (_, margin) = bot.best_move_with_margin(&game).unwrap();
+        assert!(margin.abs() < 0.01);
+    }
+    
+    #[test]
+    fn test_well_column_stays_put() {
+        // The default weights already penalize moving the well
+        let bot = TetrisBot::new();
+        let script = [PieceType::T, PieceType::O, PieceType::S, PieceType::Z];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        game.can_hold = false;
+        
+        // A clean stack with the right column left open
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH - 1 {
+                game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+            }
+        }
+        
+        let well_column = bot.evaluator.well_column(&game.board);
+        assert_eq!(well_column, Some(BOARD_WIDTH - 1));
+        
+        assert!(bot.make_move(&mut game));
+        assert_eq!(bot.evaluator.well_column(&game.board), well_column);
+        
+        game.can_hold = false;
+        assert!(bot.make_move(&mut game));
+        assert_eq!(bot.evaluator.well_column(&game.board), well_column);
+    }
+    
+    #[test]
+    fn test_make_move_falls_back_to_drop() {
+        // A move finder that considers no moves at all finds nothing to play
+        let bot = TetrisBot::with_move_finder(MoveFinder::with_max_moves(0));
+        let mut game = Game::new();
+        assert!(bot.best_move(&game).is_none());
+        
+        // The piece still locks, dropped straight down from where it is
+        let piece_type = game.current_piece.as_ref().unwrap().piece_type;
+        assert!(bot.make_move(&mut game));
+        assert_eq!(game.stats.pieces_placed, 1);
+        let locked = (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .filter(|&(row, col)| game.board.piece_type_at(row, col) == Some(piece_type))
+            .count();
+        assert_eq!(locked, 4);
+    }
+    
+    #[test]
+    fn test_dig_weight_clears_cheese_faster() {
+        // Garbage rows left after 40 pieces on five rows of cheese, summed over a few seeds
+        let garbage_left = |dig_weight: f64| -> usize {
+            (0..6).map(|seed| {
+                let bot = TetrisBot::with_evaluator(BoardEvaluator::with_weights(EvaluationWeights {
+                    dig_weight,
+                    ..Default::default()
+                }));
+                let mut game = Game::start_with_cheese(5, seed);
+                bot.play_n(&mut game, 40);
+                (0..BOARD_HEIGHT)
+                    .filter(|&row| game.board.row(row).is_some_and(|cells| cells.contains(&Cell::Garbage)))
+                    .count()
+            }).sum()
+        };
+        
+        assert!(garbage_left(3.0) < garbage_left(0.0));
+    }
+    
+    #[test]
+    fn test_benchmark() {
+        let bot = TetrisBot::new();
+        let report = bot.benchmark(3, 100);
+        
+        assert_eq!(report.games, 3);
+        assert!(report.games_survived <= 3);
+        assert!(report.mean_lines > 0.0);
+        assert!(report.median_lines > 0.0);
+        assert!(report.max_score > 0);
+        
+        // Three games' worth of lines, so the total is a whole number
+        assert_eq!((report.mean_lines * 3.0).fract(), 0.0);
+    }
+    
+    #[test]
+    fn test_play_n() {
+        let bot = TetrisBot::new();
+        let mut game = Game::new();
+        
+        let result = bot.play_n(&mut game, 20);
+        assert!(result.placements <= 20);
+        assert!(result.placements == 20 || game.state == GameState::GameOver);
+        assert_eq!(result.lines_cleared, game.score_system.lines_cleared);
+        assert_eq!(result.score, game.score_system.score);
+        assert!(result.score > 0);
+    }
+    
+    #[test]
+    fn test_play_n_with_are() {
+        let bot = TetrisBot::new();
+        let mut game = Game::new();
+        game.set_are(Duration::from_millis(100));
+        
+        // Each placement waits out the entry delay instead of ending the run
+        let result = bot.play_n(&mut game, 10);
+        assert_eq!(result.placements, 10);
+        assert_eq!(game.stats.pieces_placed, 10);
+    }
+    
+    #[test]
+    fn test_should_hold() {
+        let bot = TetrisBot::new();
+        let mut game = Game::new();
+        
+        // Four rows waiting on an I piece in the left column
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+            for col in 1..BOARD_WIDTH {
+                game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+            }
+        }
+        
+        // An O piece can't use the well, but the held I piece can
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::O;
+            piece.row = 0;
+            piece
+        });
+        game.held_piece = Some(PieceType::I);
+        
+        assert_eq!(game.piece_after_hold(), Some(PieceType::I));
+        assert!(bot.should_hold(&game));
+        
+        // Nothing to decide once the hold is used up
+        game.can_hold = false;
+        assert_eq!(game.piece_after_hold(), None);
+        assert!(!bot.should_hold(&game));
+    }
+    
+    #[test]
+    fn test_takes_perfect_clear() {
+        let bot = TetrisBot::with_evaluator(BoardEvaluator::with_weights(EvaluationWeights {
+            perfect_clear_weight: 100.0,
+            ..Default::default()
+        }));
+        let mut game = Game::new();
+        
+        // A single row that a flat I piece on the right finishes
+        for col in 0..BOARD_WIDTH - 4 {
+            game.board.set_cell(BOARD_HEIGHT - 1, col, Cell::Filled(PieceType::O));
+        }
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::I;
+            piece
+        });
+        game.can_hold = false;
+        
+        assert!(bot.make_move(&mut game));
+        assert!(game.board.is_perfect_clear());
+    }
+    
+    #[test]
+    fn test_hard_drop_disabled() {
+        let mut game = Game::with_config(GameConfig {
+            hard_drop_enabled: false,
+            ..Default::default()
+        });
+        
+        // Hard drop does nothing at all
+        let row = game.current_piece.as_ref().unwrap().row;
+        assert!(!game.hard_drop());
+        assert_eq!(game.current_piece.as_ref().unwrap().row, row);
+        assert!(game.board.is_perfect_clear());
+        
+        // The bot soft drops its placements instead
+        let bot = TetrisBot::new();
+        let result = bot.play_n(&mut game, 5);
+        assert_eq!(result.placements, 5);
+        assert_eq!(game.stats.pieces_placed, 5);
+    }
+}
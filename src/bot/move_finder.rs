@@ -1,46 +1,13 @@
-use crate::tetris_core::{Game, BOARD_WIDTH};
+use crate::tetris_core::Game;
 
-/// Represents a move that can be performed by the bot
-#[derive(Clone, Debug)]
-pub struct Move {
-    /// Number of left movements to perform
-    pub left_moves: u8,
-    /// Number of right movements to perform
-    pub right_moves: u8,
-    /// Number of clockwise rotations to perform
-    pub clockwise_rotations: u8,
-    /// Number of counter-clockwise rotations to perform
-    pub counterclockwise_rotations: u8,
-    /// Whether to hard drop immediately
-    pub hard_drop: bool,
-    /// Whether to hold the piece
-    pub hold: bool,
-}
-
-impl Move {
-    /// Create a new move
-    pub fn new(
-        left_moves: u8,
-        right_moves: u8,
-        clockwise_rotations: u8,
-        counterclockwise_rotations: u8,
-        hard_drop: bool,
-        hold: bool,
-    ) -> Self {
-        Move {
-            left_moves,
-            right_moves,
-            clockwise_rotations,
-            counterclockwise_rotations,
-            hard_drop,
-            hold,
-        }
-    }
-}
+pub use crate::tetris_core::Move;
 
 /// Finds and applies possible moves for the Tetris bot
 pub struct MoveFinder {
     max_moves_to_consider: usize,
+    /// Leave out placements that add holes to the board without clearing lines,
+    /// unless every placement would
+    pub avoid_new_holes: bool,
 }
 
 impl MoveFinder {
@@ -48,6 +15,7 @@ impl MoveFinder {
     pub fn new() -> Self {
         MoveFinder {
             max_moves_to_consider: 500, // Limit to avoid excessive computation
+            avoid_new_holes: false,
         }
     }
     
@@ -55,168 +23,177 @@ impl MoveFinder {
     pub fn with_max_moves(max_moves: usize) -> Self {
         MoveFinder {
             max_moves_to_consider: max_moves,
+            avoid_new_holes: false,
         }
     }
     
     /// Find all possible moves for the current piece
     pub fn find_possible_moves(&self, game: &Game) -> Vec<Move> {
-        let mut moves = Vec::new();
-        
-        // Check if the current piece is valid
-        if game.current_piece.is_none() {
-            return moves;
+        if !self.avoid_new_holes {
+            return self.generate_moves(game);
         }
         
-        // Consider holding the piece first
-        if game.can_hold {
-            moves.push(Move::new(0, 0, 0, 0, true, true));
+        self.find_possible_placements(game)
+            .into_iter()
+            .map(|(possible_move, _)| possible_move)
+            .collect()
+    }
+    
+    /// Possible moves for the current piece, each with the game it leads to
+    /// Every move is simulated once; moves that can't be carried out are left out
+    pub fn find_possible_placements(&self, game: &Game) -> Vec<(Move, Game)> {
+        let placements: Vec<(Move, Game)> = self.generate_moves(game)
+            .into_iter()
+            .filter_map(|possible_move| {
+                let after = self.apply_move_cloned(game, &possible_move)?;
+                Some((possible_move, after))
+            })
+            .collect();
+        if !self.avoid_new_holes {
+            return placements;
         }
         
-        // Consider rotations: 0, 1, 2, or 3 clockwise rotations
-        for clockwise_rotations in 0..4 {
-            // For each rotation, try every possible horizontal position
-            for position in 0..BOARD_WIDTH {
-                // Calculate left or right moves needed to reach this position
-                let mut game_clone = game.clone();
-                
-                // Apply rotations
-                for _ in 0..clockwise_rotations {
-                    if !game_clone.rotate_clockwise() {
-                        break;
-                    }
-                }
-                
-                // Get the horizontal position of the piece after rotation
-                let current_position = if let Some(ref piece) = game_clone.current_piece {
-                    piece.col as usize
-                } else {
-                    continue;
-                };
-                
-                // Calculate and apply horizontal moves
-                let (left_moves, right_moves) = if position < current_position {
-                    ((current_position - position) as u8, 0)
-                } else {
-                    (0, (position - current_position) as u8)
-                };
-                
-                // Create a move and add to possible moves
-                let new_move = Move::new(
-                    left_moves,
-                    right_moves,
-                    clockwise_rotations,
-                    0,
-                    true,
-                    false
-                );
-                
-                moves.push(new_move);
-                
-                // Limit the number of moves to avoid excessive computation
-                if moves.len() >= self.max_moves_to_consider {
-                    return moves;
-                }
-            }
-        }
+        let base_holes = game.board.hole_cells().len();
+        let adds_holes: Vec<bool> = placements.iter()
+            .map(|(_, after)| Self::creates_new_holes(game, after, base_holes))
+            .collect();
         
-        // Also consider counter-clockwise rotations for more optimal moves
-        for counterclockwise_rotations in 1..4 {
-            // For each rotation, try every possible horizontal position
-            for position in 0..BOARD_WIDTH {
-                // Calculate left or right moves needed to reach this position
-                let mut game_clone = game.clone();
-                
-                // Apply rotations
-                for _ in 0..counterclockwise_rotations {
-                    if !game_clone.rotate_counterclockwise() {
-                        break;
-                    }
-                }
-                
-                // Get the horizontal position of the piece after rotation
-                let current_position = if let Some(ref piece) = game_clone.current_piece {
-                    piece.col as usize
-                } else {
-                    continue;
-                };
-                
-                // Calculate and apply horizontal moves
-                let (left_moves, right_moves) = if position < current_position {
-                    ((current_position - position) as u8, 0)
-                } else {
-                    (0, (position - current_position) as u8)
-                };
-                
-                // Create a move and add to possible moves
-                let new_move = Move::new(
-                    left_moves,
-                    right_moves,
-                    0,
-                    counterclockwise_rotations,
-                    true,
-                    false
-                );
-                
-                moves.push(new_move);
-                
-                // Limit the number of moves to avoid excessive computation
-                if moves.len() >= self.max_moves_to_consider {
-                    return moves;
-                }
-            }
+        // Better a hole than no move at all (an S or Z on a flat stack can't avoid one)
+        if adds_holes.iter().all(|&adds| adds) {
+            return placements;
         }
-        
+        placements.into_iter()
+            .zip(adds_holes)
+            .filter(|&(_, adds)| !adds)
+            .map(|(placement, _)| placement)
+            .collect()
+    }
+    
+    /// Whether the game after a move has more holes than `base_holes`, the count before it,
+    /// without any lines having been cleared
+    fn creates_new_holes(before: &Game, after: &Game, base_holes: usize) -> bool {
+        after.score_system.lines_cleared == before.score_system.lines_cleared &&
+            after.board.hole_cells().len() > base_holes
+    }
+    
+    /// Every candidate move for the current piece, up to `max_moves_to_consider`
+    fn generate_moves(&self, game: &Game) -> Vec<Move> {
+        let mut moves = game.candidate_moves();
+        moves.truncate(self.max_moves_to_consider);
         moves
     }
     
+    /// Block positions where the current piece can come to rest, one entry per distinct
+    /// placement (without holding); each entry is sorted
+    pub fn landing_positions(&self, game: &Game) -> Vec<[(i32, i32); 4]> {
+        game.landing_positions_for(&self.find_possible_moves(game))
+    }
+    
+    /// Number of distinct places the current piece can lock, i.e. the search branching factor
+    pub fn reachable_placement_count(&self, game: &Game) -> usize {
+        self.landing_positions(game).len()
+    }
+    
     /// Apply a move to the game state
     pub fn apply_move(&self, game: &mut Game, move_to_apply: &Move) -> bool {
-        // Apply hold if needed
-        if move_to_apply.hold && game.can_hold {
-            if !game.hold_piece() {
-                return false;
-            }
-        }
+        game.apply_move(move_to_apply)
+    }
+    
+    /// Apply a move to a copy of the game, leaving the original untouched
+    /// See `Game::apply_move_cloned`; returns None if the move couldn't be carried out
+    pub fn apply_move_cloned(&self, game: &Game, move_to_apply: &Move) -> Option<Game> {
+        game.apply_move_cloned(move_to_apply)
+    }
+    
+    /// Test if a move is valid by simulating it
+    pub fn is_valid_move(&self, game: &Game, move_to_test: &Move) -> bool {
+        let mut game_clone = game.clone();
+        self.apply_move(&mut game_clone, move_to_test)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tetris_core::{PieceType, BOARD_WIDTH};
+    use std::time::Duration;
+    
+    #[test]
+    fn test_apply_move_cloned() {
+        let move_finder = MoveFinder::new();
+        let game = Game::new();
+        let drop_straight = Move::new(0, 0, 0, 0, true, false);
         
-        // Apply rotations
-        for _ in 0..move_to_apply.clockwise_rotations {
-            if !game.rotate_clockwise() {
-                return false;
-            }
-        }
+        let after = move_finder.apply_move_cloned(&game, &drop_straight).unwrap();
+        assert!(!after.board.is_perfect_clear());
         
-        for _ in 0..move_to_apply.counterclockwise_rotations {
-            if !game.rotate_counterclockwise() {
-                return false;
-            }
-        }
+        // The input game is unchanged
+        assert!(game.board.is_perfect_clear());
+        assert_eq!(game.score_system.score, 0);
         
-        // Apply horizontal movements
-        for _ in 0..move_to_apply.left_moves {
-            if !game.move_left() {
-                return false;
-            }
-        }
+        // Moves that run into a wall fail
+        let too_far_left = Move::new(BOARD_WIDTH as u8, 0, 0, 0, true, false);
+        assert!(move_finder.apply_move_cloned(&game, &too_far_left).is_none());
         
-        for _ in 0..move_to_apply.right_moves {
-            if !game.move_right() {
-                return false;
-            }
-        }
+        // The copy skips the entry delay and deals the next piece
+        let mut delayed = Game::new();
+        delayed.set_are(Duration::from_millis(100));
+        let after = move_finder.apply_move_cloned(&delayed, &drop_straight).unwrap();
+        assert!(!after.is_in_are());
+        assert_eq!(after.current_piece.as_ref().map(|piece| piece.piece_type), delayed.peek_next_pieces(1).first().copied());
+    }
+    
+    #[test]
+    fn test_apply_move_cloned_skips_finesse() {
+        let move_finder = MoveFinder::new();
+        let mut game = Game::new();
+        let wasteful = Move::new(1, 1, 0, 0, true, false);
         
-        // Hard drop if needed
-        if move_to_apply.hard_drop {
-            if !game.hard_drop() {
-                return false;
-            }
-        }
+        // Only a real lock counts the wasted inputs
+        let after = move_finder.apply_move_cloned(&game, &wasteful).unwrap();
+        assert_eq!(after.stats.finesse_faults, 0);
+        assert!(move_finder.apply_move(&mut game, &wasteful));
+        assert_eq!(game.stats.finesse_faults, 1);
+    }
+    
+    #[test]
+    fn test_avoid_new_holes() {
+        let mut game = Game::new();
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::T;
+            piece
+        });
+        game.can_hold = false;
         
-        true
+        // A T pointing down onto the empty floor leaves a hole on either side of its stem
+        let mut move_finder = MoveFinder::new();
+        let creates_holes = |move_finder: &MoveFinder| move_finder.find_possible_placements(&game)
+            .iter()
+            .any(|(_, after)| !after.board.hole_cells().is_empty());
+        assert!(creates_holes(&move_finder));
+        
+        move_finder.avoid_new_holes = true;
+        assert!(!creates_holes(&move_finder));
+        
+        // The simulated games are the ones the kept moves lead to
+        let placements = move_finder.find_possible_placements(&game);
+        assert!(!placements.is_empty());
+        for (possible_move, after) in &placements {
+            assert_eq!(move_finder.apply_move_cloned(&game, possible_move).unwrap().board, after.board);
+        }
+        let moves: Vec<Move> = placements.into_iter().map(|(possible_move, _)| possible_move).collect();
+        assert_eq!(move_finder.find_possible_moves(&game), moves);
     }
     
-    /// Test if a move is valid by simulating it
-    pub fn is_valid_move(&self, game: &Game, move_to_test: &Move) -> bool {
-        let mut game_clone = game.clone();
-        self.apply_move(&mut game_clone, move_to_test)
+    #[test]
+    fn test_reachable_placement_count() {
+        let mut game = Game::new();
+        game.current_piece = game.current_piece.take().map(|mut piece| {
+            piece.piece_type = PieceType::O;
+            piece
+        });
+        
+        // Every rotation of an O piece looks the same, so only the column matters
+        assert_eq!(MoveFinder::new().reachable_placement_count(&game), BOARD_WIDTH - 1);
     }
-}
\ No newline at end of file
+}
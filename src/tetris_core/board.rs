@@ -1,11 +1,55 @@
-use super::{BOARD_WIDTH, BOARD_HEIGHT};
+use super::{BOARD_WIDTH, BOARD_HEIGHT, VISIBLE_HEIGHT};
+use std::fmt;
 use super::piece::{Piece, PieceType};
 
+// Largest width or height `Board::with_dimensions` accepts before calling the request absurd
+const MAX_BOARD_DIMENSION: usize = 1000;
+
 /// Represents a cell in the Tetris board
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Cell {
     Empty,
     Filled(PieceType), // Stores the piece type for color information
+    Garbage,           // Garbage rows received from an opponent
+}
+
+// Rows in a fumen field, not counting the garbage row below it
+const FUMEN_FIELD_HEIGHT: usize = 23;
+
+// RGBA colors used when rendering the board to a pixel buffer
+const EMPTY_COLOR: [u8; 4] = [0, 0, 0, 255];
+const GARBAGE_COLOR: [u8; 4] = [128, 128, 128, 255];
+const GRID_COLOR: [u8; 4] = [40, 40, 40, 255];
+
+impl Cell {
+    /// Whether the cell is occupied by a block of any kind
+    pub fn is_filled(&self) -> bool {
+        *self != Cell::Empty
+    }
+
+    /// Fumen color index of the cell (0 empty, 1-7 I L O Z T J S, 8 gray)
+    pub fn fumen_index(&self) -> u8 {
+        match self {
+            Cell::Empty => 0,
+            Cell::Filled(PieceType::I) => 1,
+            Cell::Filled(PieceType::L) => 2,
+            Cell::Filled(PieceType::O) => 3,
+            Cell::Filled(PieceType::Z) => 4,
+            Cell::Filled(PieceType::T) => 5,
+            Cell::Filled(PieceType::J) => 6,
+            Cell::Filled(PieceType::S) => 7,
+            Cell::Garbage => 8,
+        }
+    }
+
+    /// RGBA color used to draw the cell
+    pub fn color(&self) -> [u8; 4] {
+        match self {
+            Cell::Empty => EMPTY_COLOR,
+            Cell::Filled(piece_type) => piece_type.color(),
+            Cell::Garbage => GARBAGE_COLOR,
+        }
+    }
 }
 
 impl Default for Cell {
@@ -14,7 +58,76 @@ impl Default for Cell {
     }
 }
 
+/// Errors from constructing a board
+#[derive(Debug, PartialEq)]
+pub enum BoardError {
+    /// Zero-sized, or larger than the allowed maximum
+    InvalidDimensions { width: usize, height: usize },
+    /// A sensible size, but not one the fixed-size board storage can hold
+    UnsupportedDimensions { width: usize, height: usize },
+    /// A layout row (counted from the top of the layout) that isn't `BOARD_WIDTH` characters
+    InvalidRowWidth { row: usize, width: usize },
+    /// A layout with more rows than the board has
+    TooManyRows(usize),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::InvalidDimensions { width, height } => {
+                write!(f, "invalid board dimensions {}x{}", width, height)
+            }
+            BoardError::UnsupportedDimensions { width, height } => write!(
+                f,
+                "unsupported board dimensions {}x{} (only {}x{} is available)",
+                width, height, BOARD_WIDTH, BOARD_HEIGHT
+            ),
+            BoardError::InvalidRowWidth { row, width } => write!(
+                f,
+                "layout row {} is {} cells wide (expected {})",
+                row, width, BOARD_WIDTH
+            ),
+            BoardError::TooManyRows(rows) => write!(
+                f,
+                "layout has {} rows but the board only has {}",
+                rows, BOARD_HEIGHT
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+/// How blocks settle once the lines beneath them are cleared
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GravityMode {
+    /// Rows above a clear drop by whole rows, so blocks may be left hanging (guideline)
+    Naive,
+    /// Connected clumps of blocks keep falling until they rest on something (cascade)
+    Sticky,
+}
+
+/// An invariant found broken by `Board::validate`
+#[derive(Debug, PartialEq)]
+pub enum BoardInvariantError {
+    /// A clump of blocks not connected to the floor; the cell is its first in row-major order
+    FloatingClump { row: usize, col: usize },
+}
+
+impl fmt::Display for BoardInvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardInvariantError::FloatingClump { row, col } => {
+                write!(f, "floating clump of blocks at row {}, column {}", row, col)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardInvariantError {}
+
 /// Represents the Tetris game board
+#[derive(Clone, PartialEq, Debug)]
 pub struct Board {
     grid: [[Cell; BOARD_WIDTH]; BOARD_HEIGHT],
 }
@@ -26,6 +139,72 @@ impl Board {
             grid: [[Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT],
         }
     }
+    
+    /// Creates an empty board of the given size, rejecting zero or absurdly large sizes
+    /// The board is currently stored in fixed-size arrays, so only the standard
+    /// `BOARD_WIDTH` x `BOARD_HEIGHT` size can actually be built
+    pub fn with_dimensions(width: usize, height: usize) -> Result<Board, BoardError> {
+        Self::with_dimensions_capped(width, height, MAX_BOARD_DIMENSION)
+    }
+    
+    /// Like `with_dimensions`, with a custom cap on the width and height
+    pub fn with_dimensions_capped(width: usize, height: usize, max_dimension: usize) -> Result<Board, BoardError> {
+        if width == 0 || height == 0 || width > max_dimension || height > max_dimension {
+            return Err(BoardError::InvalidDimensions { width, height });
+        }
+        if width != BOARD_WIDTH || height != BOARD_HEIGHT {
+            return Err(BoardError::UnsupportedDimensions { width, height });
+        }
+        Ok(Board::new())
+    }
+    
+    /// Build a board from a text layout, one string per row from top to bottom, with the
+    /// last row on the bottom of the board
+    /// `.` and spaces are empty, piece letters (`IOTSZJL`, either case) fill the cell with
+    /// that piece type, and any other character is garbage
+    pub fn from_rows(rows: &[&str]) -> Result<Board, BoardError> {
+        if rows.len() > BOARD_HEIGHT {
+            return Err(BoardError::TooManyRows(rows.len()));
+        }
+        
+        let mut board = Board::new();
+        let top = BOARD_HEIGHT - rows.len();
+        for (index, text) in rows.iter().enumerate() {
+            let width = text.chars().count();
+            if width != BOARD_WIDTH {
+                return Err(BoardError::InvalidRowWidth { row: index, width });
+            }
+            
+            for (col, symbol) in text.chars().enumerate() {
+                board.grid[top + index][col] = match symbol.to_ascii_uppercase() {
+                    '.' | ' ' => Cell::Empty,
+                    'I' => Cell::Filled(PieceType::I),
+                    'O' => Cell::Filled(PieceType::O),
+                    'T' => Cell::Filled(PieceType::T),
+                    'S' => Cell::Filled(PieceType::S),
+                    'Z' => Cell::Filled(PieceType::Z),
+                    'J' => Cell::Filled(PieceType::J),
+                    'L' => Cell::Filled(PieceType::L),
+                    _ => Cell::Garbage,
+                };
+            }
+        }
+        
+        Ok(board)
+    }
+    
+    /// Whether the cell is filled, treating the walls and floor as filled
+    /// Cells above the top row count as filled when `solid_ceiling` is set
+    /// Used by spin detection, which inspects cells around the piece that may be off the board
+    pub fn is_filled_or_wall(&self, row: i32, col: i32, solid_ceiling: bool) -> bool {
+        if col < 0 || col >= BOARD_WIDTH as i32 || row >= BOARD_HEIGHT as i32 {
+            return true;
+        }
+        if row < 0 {
+            return solid_ceiling;
+        }
+        self.grid[row as usize][col as usize].is_filled()
+    }
 
     /// Gets the cell at the specified coordinates
     pub fn get_cell(&self, row: usize, col: usize) -> Option<&Cell> {
@@ -36,6 +215,24 @@ impl Board {
         }
     }
 
+    /// The piece type stored in the cell, or None for empty, garbage and off-board cells
+    pub fn piece_type_at(&self, row: usize, col: usize) -> Option<PieceType> {
+        match self.get_cell(row, col) {
+            Some(&Cell::Filled(piece_type)) => Some(piece_type),
+            _ => None,
+        }
+    }
+
+    /// The cells of a row, left to right
+    pub fn row(&self, row: usize) -> Option<&[Cell]> {
+        self.grid.get(row).map(|cells| cells.as_slice())
+    }
+    
+    /// The cells of a column, top to bottom (nothing if out of bounds)
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().filter_map(move |cells| cells.get(col))
+    }
+    
     /// Sets the cell at the specified coordinates
     pub fn set_cell(&mut self, row: usize, col: usize, cell: Cell) -> bool {
         if row < BOARD_HEIGHT && col < BOARD_WIDTH {
@@ -48,14 +245,19 @@ impl Board {
 
     /// Checks if a piece can be placed at the specified position
     pub fn can_place(&self, piece: &Piece) -> bool {
-        for &(row, col) in &piece.get_blocks() {
-            // Out of bounds check
-            if row >= BOARD_HEIGHT || col >= BOARD_WIDTH {
+        for &(row, col) in &piece.get_block_positions() {
+            // Out of bounds check (walls and floor)
+            if col < 0 || col >= BOARD_WIDTH as i32 || row >= BOARD_HEIGHT as i32 {
                 return false;
             }
             
+            // Blocks above the top of the board can't collide with anything
+            if row < 0 {
+                continue;
+            }
+            
             // Collision check
-            if let Some(Cell::Filled(_)) = self.get_cell(row, col) {
+            if self.grid[row as usize][col as usize].is_filled() {
                 return false;
             }
         }
@@ -74,33 +276,49 @@ impl Board {
         true
     }
 
+    /// Writes `cell` into every on-board cell of the piece, without checking for collisions
+    /// Meant for drawing overlays (active piece, ghost) onto a reused scratch board
+    pub fn paint_piece(&mut self, piece: &Piece, cell: Cell) {
+        for (row, col) in piece.get_block_positions() {
+            if row >= 0 && col >= 0 {
+                self.set_cell(row as usize, col as usize, cell);
+            }
+        }
+    }
+
+    /// Empties the piece's cells again after `paint_piece`
+    pub fn erase_piece(&mut self, piece: &Piece) {
+        self.paint_piece(piece, Cell::Empty);
+    }
+
     /// Clears completed lines and returns the number of lines cleared
     pub fn clear_lines(&mut self) -> usize {
         let mut lines_cleared = 0;
         
         // Check each row, starting from the bottom
-        for row in (0..BOARD_HEIGHT).rev() {
-            if self.is_line_complete(row) {
-                self.remove_line(row);
+        let mut row = BOARD_HEIGHT;
+        while row > 0 {
+            if self.is_line_complete(row - 1) {
+                // The row above shifts into this one, so check the same index again
+                self.remove_line(row - 1);
                 lines_cleared += 1;
+            } else {
+                row -= 1;
             }
         }
         
         lines_cleared
     }
 
+    /// Counts the filled cells in a row (zero for rows outside the board)
+    pub fn filled_count_in_row(&self, row: usize) -> usize {
+        self.row(row).map_or(0, |cells| cells.iter().filter(|cell| cell.is_filled()).count())
+    }
+
     /// Checks if a line is complete (all cells filled)
-    fn is_line_complete(&self, row: usize) -> bool {
-        if row >= BOARD_HEIGHT {
-            return false;
-        }
-        
-        for col in 0..BOARD_WIDTH {
-            if let Cell::Empty = self.grid[row][col] {
-                return false;
-            }
-        }
-        true
+    /// Garbage counts as filled, so a garbage row only completes once its gap is filled in
+    pub fn is_line_complete(&self, row: usize) -> bool {
+        self.filled_count_in_row(row) == BOARD_WIDTH
     }
 
     /// Removes a line and shifts all lines above down
@@ -118,16 +336,190 @@ impl Board {
         self.grid[0] = [Cell::Empty; BOARD_WIDTH];
     }
 
+    /// Pushes the stack up and fills the bottom `count` rows with garbage,
+    /// leaving the cell in `gap_col` empty in each row
+    pub fn insert_garbage_rows(&mut self, count: usize, gap_col: usize) {
+        let count = count.min(BOARD_HEIGHT);
+        
+        // Shift everything up; blocks pushed past the top are lost
+        for row in 0..BOARD_HEIGHT - count {
+            self.grid[row] = self.grid[row + count];
+        }
+        
+        let mut garbage_row = [Cell::Garbage; BOARD_WIDTH];
+        if gap_col < BOARD_WIDTH {
+            garbage_row[gap_col] = Cell::Empty;
+        }
+        for row in BOARD_HEIGHT - count..BOARD_HEIGHT {
+            self.grid[row] = garbage_row;
+        }
+    }
+
     /// Checks if the board has collisions at the spawn point
     pub fn is_top_blocked(&self) -> bool {
         for col in 0..BOARD_WIDTH {
-            if let Cell::Filled(_) = self.grid[0][col] {
+            if self.grid[0][col].is_filled() {
                 return true;
             }
         }
         false
     }
 
+    /// Counts filled cells that have at least one empty cell somewhere below them
+    /// Unlike holes (which count the empty cells), this counts the covering blocks
+    pub fn count_overhangs(&self) -> u32 {
+        let mut overhangs = 0;
+        
+        for col in 0..BOARD_WIDTH {
+            // Scan bottom-up, remembering whether an empty cell has been seen yet
+            let mut empty_below = false;
+            for row in (0..BOARD_HEIGHT).rev() {
+                match self.grid[row][col] {
+                    Cell::Empty => empty_below = true,
+                    Cell::Filled(_) | Cell::Garbage => {
+                        if empty_below {
+                            overhangs += 1;
+                        }
+                    }
+                }
+            }
+        }
+        
+        overhangs
+    }
+    
+    /// Coordinates (row, col) of every hole: an empty cell with a filled cell somewhere above it
+    /// Listed top to bottom, left to right
+    pub fn hole_cells(&self) -> Vec<(usize, usize)> {
+        let mut holes = Vec::new();
+        let mut covered = [false; BOARD_WIDTH];
+        
+        for (row, cells) in self.grid.iter().enumerate() {
+            for (col, (cell, covered)) in cells.iter().zip(covered.iter_mut()).enumerate() {
+                if cell.is_filled() {
+                    *covered = true;
+                } else if *covered {
+                    holes.push((row, col));
+                }
+            }
+        }
+        
+        holes
+    }
+
+    /// Holes bucketed by how many filled cells sit above them in their column
+    /// Index `d` counts the holes buried under exactly `d` cells; empty if there are no holes
+    pub fn hole_depth_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        
+        for col in 0..BOARD_WIDTH {
+            let mut filled_above = 0;
+            for cell in self.column(col) {
+                if cell.is_filled() {
+                    filled_above += 1;
+                } else if filled_above > 0 {
+                    if histogram.len() <= filled_above {
+                        histogram.resize(filled_above + 1, 0);
+                    }
+                    histogram[filled_above] += 1;
+                }
+            }
+        }
+        
+        histogram
+    }
+    
+    /// Check that the board is one play under `gravity_mode` could produce
+    /// Cells can't be out of bounds in the fixed-size grid, so every naive-gravity board
+    /// passes; sticky gravity also requires each block to connect to the floor through
+    /// orthogonally adjacent blocks
+    pub fn validate(&self, gravity_mode: GravityMode) -> Result<(), BoardInvariantError> {
+        if gravity_mode == GravityMode::Naive {
+            return Ok(());
+        }
+        
+        // Flood fill from the filled cells on the floor
+        let mut supported = [[false; BOARD_WIDTH]; BOARD_HEIGHT];
+        let mut stack: Vec<(usize, usize)> = (0..BOARD_WIDTH)
+            .filter(|&col| self.grid[BOARD_HEIGHT - 1][col].is_filled())
+            .map(|col| (BOARD_HEIGHT - 1, col))
+            .collect();
+        while let Some((row, col)) = stack.pop() {
+            if supported[row][col] {
+                continue;
+            }
+            supported[row][col] = true;
+            
+            let neighbors = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (next_row, next_col) in neighbors {
+                if self.get_cell(next_row, next_col).is_some_and(Cell::is_filled) && !supported[next_row][next_col] {
+                    stack.push((next_row, next_col));
+                }
+            }
+        }
+        
+        for (row, cells) in self.grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if cell.is_filled() && !supported[row][col] {
+                    return Err(BoardInvariantError::FloatingClump { row, col });
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// The board as a fumen field: 23 rows of 10 fumen color indices, top to bottom and
+    /// left to right, with the bottom rows lined up and the rows above the board left empty
+    /// The garbage row fumen keeps below the field is not included
+    pub fn to_fumen_field(&self) -> Vec<u8> {
+        let mut field = vec![0; (FUMEN_FIELD_HEIGHT - BOARD_HEIGHT) * BOARD_WIDTH];
+        for cells in self.grid.iter() {
+            field.extend(cells.iter().map(Cell::fumen_index));
+        }
+        field
+    }
+
+    /// Computes a cheap rolling checksum over all cells
+    /// Intended for coarse desync detection in replays; collisions are possible
+    pub fn checksum(&self) -> u32 {
+        let mut hash: u32 = 17;
+        
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                let value = match self.grid[row][col] {
+                    Cell::Empty => 0,
+                    Cell::Filled(piece_type) => piece_type as u32 + 1,
+                    Cell::Garbage => 8,
+                };
+                hash = hash.wrapping_mul(31).wrapping_add(value);
+            }
+        }
+        
+        hash
+    }
+
+    /// Checks that the cells new pieces spawn into (the center columns of the
+    /// hidden buffer rows) are all empty
+    pub fn is_spawn_region_clear(&self) -> bool {
+        let spawn_col = BOARD_WIDTH / 2 - 1;
+        
+        // Spawned pieces reach one column left and two columns right of the spawn column
+        for row in 0..BOARD_HEIGHT - VISIBLE_HEIGHT {
+            for col in spawn_col - 1..=spawn_col + 2 {
+                if self.grid[row][col].is_filled() {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
     /// Clears the entire board
     pub fn clear(&mut self) {
         for row in 0..BOARD_HEIGHT {
@@ -141,13 +533,117 @@ impl Board {
     pub fn is_perfect_clear(&self) -> bool {
         for row in 0..BOARD_HEIGHT {
             for col in 0..BOARD_WIDTH {
-                if let Cell::Filled(_) = self.grid[row][col] {
+                if self.grid[row][col].is_filled() {
                     return false;
                 }
             }
         }
         true
     }
+    
+    /// How flat the stack's surface is: the negative variance of the column heights
+    /// 0.0 for a perfectly flat surface, lower the more uneven the columns are
+    /// Unlike bumpiness this compares every column against the average, not just neighbors
+    pub fn flatness(&self) -> f64 {
+        let heights: Vec<f64> = (0..BOARD_WIDTH)
+            .map(|col| {
+                let top = (0..BOARD_HEIGHT).find(|&row| self.grid[row][col].is_filled());
+                top.map_or(0.0, |row| (BOARD_HEIGHT - row) as f64)
+            })
+            .collect();
+        
+        let mean = heights.iter().sum::<f64>() / BOARD_WIDTH as f64;
+        let variance = heights.iter().map(|height| (height - mean).powi(2)).sum::<f64>() / BOARD_WIDTH as f64;
+        -variance
+    }
+    
+    /// Whether the board is its own left-right mirror image (column `c` matches column `width - 1 - c`)
+    /// With `match_colors` the mirrored cells must be identical, otherwise only filled vs empty counts
+    pub fn is_horizontally_symmetric(&self, match_colors: bool) -> bool {
+        self.grid.iter().all(|cells| {
+            cells.iter().zip(cells.iter().rev()).all(|(cell, mirrored)| {
+                if match_colors {
+                    cell == mirrored
+                } else {
+                    cell.is_filled() == mirrored.is_filled()
+                }
+            })
+        })
+    }
+
+    /// A standalone copy of the `VISIBLE_HEIGHT` visible rows, dropping the hidden buffer
+    /// Row 0 of the copy is the top visible row
+    pub fn visible_board(&self) -> VisibleBoard {
+        let mut grid = [[Cell::Empty; BOARD_WIDTH]; VISIBLE_HEIGHT];
+        grid.copy_from_slice(self.visible_rows());
+        VisibleBoard { grid }
+    }
+    
+    /// The `VISIBLE_HEIGHT` rows of the playfield, top to bottom, without the buffer
+    pub fn visible_rows(&self) -> &[[Cell; BOARD_WIDTH]] {
+        &self.grid[BOARD_HEIGHT - VISIBLE_HEIGHT..]
+    }
+    
+    /// Render the whole board to an RGBA pixel buffer, `cell_size` pixels per cell
+    /// Returns (width, height, pixels) with pixels in row-major order
+    pub fn to_rgba(&self, cell_size: usize) -> (usize, usize, Vec<u8>) {
+        self.to_rgba_with_grid(cell_size, false)
+    }
+    
+    /// Like `to_rgba`, optionally drawing grid lines along each cell's right and bottom edges
+    pub fn to_rgba_with_grid(&self, cell_size: usize, grid_lines: bool) -> (usize, usize, Vec<u8>) {
+        render_rgba(&self.grid, cell_size, grid_lines)
+    }
+}
+
+/// The visible playfield on its own, without the hidden buffer rows, as returned by
+/// `Board::visible_board`
+#[derive(Clone, PartialEq, Debug)]
+pub struct VisibleBoard {
+    grid: [[Cell; BOARD_WIDTH]; VISIBLE_HEIGHT],
+}
+
+impl VisibleBoard {
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        BOARD_WIDTH
+    }
+
+    /// Number of rows, always `VISIBLE_HEIGHT`
+    pub fn height(&self) -> usize {
+        VISIBLE_HEIGHT
+    }
+
+    /// Gets the cell at the specified coordinates, counting rows from the top visible row
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row).and_then(|cells| cells.get(col))
+    }
+
+    /// Render the visible rows to an RGBA pixel buffer, as `Board::to_rgba` does for the whole board
+    pub fn to_rgba(&self, cell_size: usize) -> (usize, usize, Vec<u8>) {
+        render_rgba(&self.grid, cell_size, false)
+    }
+}
+
+// Render rows of cells to an RGBA pixel buffer, `cell_size` pixels per cell
+fn render_rgba(grid: &[[Cell; BOARD_WIDTH]], cell_size: usize, grid_lines: bool) -> (usize, usize, Vec<u8>) {
+    let width = BOARD_WIDTH * cell_size;
+    let height = grid.len() * cell_size;
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    
+    for y in 0..height {
+        for x in 0..width {
+            let on_grid_line = grid_lines && (x % cell_size == cell_size - 1 || y % cell_size == cell_size - 1);
+            let color = if on_grid_line {
+                GRID_COLOR
+            } else {
+                grid[y / cell_size][x / cell_size].color()
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+    
+    (width, height, pixels)
 }
 
 #[cfg(test)]
@@ -174,4 +670,363 @@ mod tests {
         // Should be a perfect clear again
         assert!(board.is_perfect_clear());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_can_place_at_edges() {
+        let board = Board::new();
+        
+        // A flat I hanging one cell past the left wall doesn't fit
+        assert!(!board.can_place(&Piece::new(PieceType::I, 5, 0)));
+        assert!(board.can_place(&Piece::new(PieceType::I, 5, 1)));
+        
+        // A vertical I poking above the top is fine, but not one past the floor
+        let mut piece = Piece::new(PieceType::I, 0, 4);
+        piece.rotate_counterclockwise();
+        assert!(board.can_place(&piece));
+        piece.row = BOARD_HEIGHT as i32 - 1;
+        assert!(!board.can_place(&piece));
+    }
+
+    #[test]
+    fn test_clear_adjacent_lines() {
+        let mut board = Board::new();
+        
+        // Two full rows on top of each other, with a marker block above them
+        for row in BOARD_HEIGHT - 2..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                board.set_cell(row, col, Cell::Filled(PieceType::O));
+            }
+        }
+        board.set_cell(BOARD_HEIGHT - 3, 0, Cell::Filled(PieceType::T));
+        
+        // Both rows clear and the marker drops to the floor
+        assert_eq!(board.clear_lines(), 2);
+        assert_eq!(board.get_cell(BOARD_HEIGHT - 1, 0), Some(&Cell::Filled(PieceType::T)));
+        assert_eq!(board.get_cell(BOARD_HEIGHT - 2, 0), Some(&Cell::Empty));
+    }
+
+    #[test]
+    fn test_count_overhangs() {
+        let mut board = Board::new();
+        
+        // A single block floating two cells above the floor
+        board.set_cell(BOARD_HEIGHT - 3, 4, Cell::Filled(PieceType::T));
+        assert_eq!(board.count_overhangs(), 1);
+        
+        // Supporting blocks next to it are not overhangs
+        board.set_cell(BOARD_HEIGHT - 1, 5, Cell::Filled(PieceType::T));
+        assert_eq!(board.count_overhangs(), 1);
+    }
+
+    #[test]
+    fn test_garbage_row_needs_gap_filled() {
+        let mut board = Board::new();
+        board.insert_garbage_rows(1, 3);
+        assert!(!board.is_line_complete(BOARD_HEIGHT - 1));
+        assert_eq!(board.clear_lines(), 0);
+        assert_eq!(board.filled_count_in_row(BOARD_HEIGHT - 1), BOARD_WIDTH - 1);
+        
+        board.set_cell(BOARD_HEIGHT - 1, 3, Cell::Filled(PieceType::I));
+        assert!(board.is_line_complete(BOARD_HEIGHT - 1));
+        assert_eq!(board.clear_lines(), 1);
+        assert!(board.is_perfect_clear());
+    }
+
+    #[test]
+    fn test_hole_cells() {
+        let mut board = Board::new();
+        assert!(board.hole_cells().is_empty());
+        
+        // A bottom row with a gap in column 2, buried under a block
+        for col in 0..BOARD_WIDTH {
+            if col != 2 {
+                board.set_cell(BOARD_HEIGHT - 1, col, Cell::Garbage);
+            }
+        }
+        assert!(board.hole_cells().is_empty());
+        board.set_cell(BOARD_HEIGHT - 2, 2, Cell::Filled(PieceType::T));
+        assert_eq!(board.hole_cells(), vec![(BOARD_HEIGHT - 1, 2)]);
+    }
+
+    #[test]
+    fn test_paint_and_erase_piece() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 0, Cell::Garbage);
+        let original = board.clone();
+        
+        let piece = Piece::new(PieceType::T, 10, 4);
+        board.paint_piece(&piece, Cell::Filled(PieceType::T));
+        for (row, col) in piece.get_blocks() {
+            assert_eq!(board.get_cell(row, col), Some(&Cell::Filled(PieceType::T)));
+        }
+        
+        board.erase_piece(&piece);
+        assert_eq!(board, original);
+    }
+
+    #[test]
+    fn test_is_horizontally_symmetric() {
+        let mut board = Board::new();
+        assert!(board.is_horizontally_symmetric(true));
+        
+        // A stack stepping up towards both walls, with J on the left mirrored by L on the right
+        for (row, width) in [(BOARD_HEIGHT - 1, 4), (BOARD_HEIGHT - 2, 2)] {
+            for col in 0..width {
+                board.set_cell(row, col, Cell::Filled(PieceType::J));
+                board.set_cell(row, BOARD_WIDTH - 1 - col, Cell::Filled(PieceType::L));
+            }
+        }
+        assert!(board.is_horizontally_symmetric(false));
+        assert!(!board.is_horizontally_symmetric(true));
+        
+        // One extra block breaks the mirror
+        board.set_cell(BOARD_HEIGHT - 3, 0, Cell::Garbage);
+        assert!(!board.is_horizontally_symmetric(false));
+    }
+
+    #[test]
+    fn test_hole_depth_histogram() {
+        let mut board = Board::new();
+        assert!(board.hole_depth_histogram().is_empty());
+        
+        // A hole under one cell in column 0 and another under three cells in column 5
+        board.set_cell(BOARD_HEIGHT - 2, 0, Cell::Garbage);
+        for row in BOARD_HEIGHT - 4..BOARD_HEIGHT - 1 {
+            board.set_cell(row, 5, Cell::Filled(PieceType::I));
+        }
+        assert_eq!(board.hole_depth_histogram(), vec![0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_to_fumen_field() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 0, Cell::Garbage);
+        board.set_cell(BOARD_HEIGHT - 1, BOARD_WIDTH - 1, Cell::Filled(PieceType::T));
+        board.set_cell(BOARD_HEIGHT - 2, 3, Cell::Filled(PieceType::S));
+        board.set_cell(0, 0, Cell::Filled(PieceType::I));
+        
+        let field = board.to_fumen_field();
+        assert_eq!(field.len(), 230);
+        assert_eq!(field[220], 8);
+        assert_eq!(field[229], 5);
+        assert_eq!(field[213], 7);
+        
+        // The board's top row is the field's second row
+        assert_eq!(field[10], 1);
+        assert_eq!(field.iter().filter(|&&index| index != 0).count(), 4);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut board = Board::new();
+        let mut other = Board::new();
+        board.set_cell(20, 3, Cell::Filled(PieceType::L));
+        other.set_cell(20, 3, Cell::Filled(PieceType::L));
+        assert_eq!(board.checksum(), other.checksum());
+        
+        // A single changed cell changes the checksum
+        other.set_cell(21, 3, Cell::Filled(PieceType::L));
+        assert_ne!(board.checksum(), other.checksum());
+        
+        // So does a different piece type in the same cell
+        other.set_cell(21, 3, Cell::Empty);
+        other.set_cell(20, 3, Cell::Filled(PieceType::J));
+        assert_ne!(board.checksum(), other.checksum());
+    }
+
+    #[test]
+    fn test_is_spawn_region_clear() {
+        let mut board = Board::new();
+        assert!(board.is_spawn_region_clear());
+        
+        // Blocks outside the spawn columns don't matter
+        board.set_cell(0, 0, Cell::Filled(PieceType::I));
+        assert!(board.is_spawn_region_clear());
+        
+        board.set_cell(1, 5, Cell::Filled(PieceType::I));
+        assert!(!board.is_spawn_region_clear());
+        
+        board.set_cell(1, 5, Cell::Empty);
+        assert!(board.is_spawn_region_clear());
+    }
+
+    #[test]
+    fn test_filled_count_in_row() {
+        let mut board = Board::new();
+        for col in 0..BOARD_WIDTH {
+            board.set_cell(BOARD_HEIGHT - 1, col, Cell::Filled(PieceType::Z));
+        }
+        board.set_cell(BOARD_HEIGHT - 2, 0, Cell::Filled(PieceType::Z));
+        
+        assert_eq!(board.filled_count_in_row(BOARD_HEIGHT - 1), BOARD_WIDTH);
+        assert!(board.is_line_complete(BOARD_HEIGHT - 1));
+        assert_eq!(board.filled_count_in_row(BOARD_HEIGHT - 2), 1);
+        assert!(!board.is_line_complete(BOARD_HEIGHT - 2));
+        assert_eq!(board.filled_count_in_row(BOARD_HEIGHT), 0);
+    }
+    
+    #[test]
+    fn test_to_rgba() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 2, Cell::Filled(PieceType::T));
+        
+        let cell_size = 4;
+        let (width, height, pixels) = board.to_rgba(cell_size);
+        assert_eq!((width, height), (BOARD_WIDTH * cell_size, BOARD_HEIGHT * cell_size));
+        assert_eq!(pixels.len(), width * height * 4);
+        
+        // Every pixel of the filled cell uses the piece color
+        for y in (BOARD_HEIGHT - 1) * cell_size..BOARD_HEIGHT * cell_size {
+            for x in 2 * cell_size..3 * cell_size {
+                let index = (y * width + x) * 4;
+                assert_eq!(pixels[index..index + 4], PieceType::T.color());
+            }
+        }
+        assert_eq!(pixels[0..4], Cell::Empty.color());
+        
+        // Grid lines replace the last pixel row and column of each cell
+        let (_, _, grid_pixels) = board.to_rgba_with_grid(cell_size, true);
+        let corner = ((cell_size - 1) * width + cell_size - 1) * 4;
+        assert_eq!(grid_pixels[corner..corner + 4], GRID_COLOR);
+        assert_eq!(grid_pixels[0..4], Cell::Empty.color());
+    }
+    
+    #[test]
+    fn test_visible_board() {
+        let mut board = Board::new();
+        board.set_cell(0, 4, Cell::Garbage);
+        board.set_cell(BOARD_HEIGHT - 1, 4, Cell::Filled(PieceType::L));
+        
+        // Only the visible block survives, moved up by the height of the buffer
+        let visible = board.visible_board();
+        assert_eq!((visible.width(), visible.height()), (BOARD_WIDTH, VISIBLE_HEIGHT));
+        assert_eq!(visible.get_cell(VISIBLE_HEIGHT - 1, 4), Some(&Cell::Filled(PieceType::L)));
+        assert_eq!(visible.get_cell(VISIBLE_HEIGHT, 4), None);
+        let filled = (0..VISIBLE_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .filter(|&(row, col)| visible.get_cell(row, col).is_some_and(|cell| cell.is_filled()))
+            .count();
+        assert_eq!(filled, 1);
+        
+        let (width, height, pixels) = visible.to_rgba(2);
+        assert_eq!((width, height), (BOARD_WIDTH * 2, VISIBLE_HEIGHT * 2));
+        assert_eq!(pixels.len(), width * height * 4);
+        
+        let rows = board.visible_rows();
+        assert_eq!(rows.len(), VISIBLE_HEIGHT);
+        assert_eq!(rows[VISIBLE_HEIGHT - 1][4], Cell::Filled(PieceType::L));
+        assert!(rows.iter().flatten().filter(|cell| cell.is_filled()).count() == 1);
+    }
+    
+    #[test]
+    fn test_row_and_column() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 3, Cell::Filled(PieceType::S));
+        board.set_cell(BOARD_HEIGHT - 1, 7, Cell::Garbage);
+        
+        let row = board.row(BOARD_HEIGHT - 1).unwrap();
+        assert_eq!(row.len(), BOARD_WIDTH);
+        for (col, cell) in row.iter().enumerate() {
+            assert_eq!(Some(cell), board.get_cell(BOARD_HEIGHT - 1, col));
+        }
+        assert!(board.row(BOARD_HEIGHT).is_none());
+        
+        let column: Vec<&Cell> = board.column(3).collect();
+        assert_eq!(column.len(), BOARD_HEIGHT);
+        assert_eq!(column[BOARD_HEIGHT - 1], &Cell::Filled(PieceType::S));
+        assert_eq!(board.column(BOARD_WIDTH).count(), 0);
+    }
+    
+    #[test]
+    fn test_is_filled_or_wall() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 0, Cell::Garbage);
+        
+        assert!(!board.is_filled_or_wall(5, 5, true));
+        assert!(board.is_filled_or_wall(BOARD_HEIGHT as i32 - 1, 0, true));
+        assert!(board.is_filled_or_wall(5, -1, true));
+        assert!(board.is_filled_or_wall(5, BOARD_WIDTH as i32, false));
+        assert!(board.is_filled_or_wall(BOARD_HEIGHT as i32, 5, false));
+        
+        // Above the top row follows the ceiling setting
+        assert!(board.is_filled_or_wall(-1, 5, true));
+        assert!(!board.is_filled_or_wall(-1, 5, false));
+    }
+    
+    #[test]
+    fn test_flatness() {
+        let mut flat = Board::new();
+        for row in BOARD_HEIGHT - 3..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                flat.set_cell(row, col, Cell::Garbage);
+            }
+        }
+        assert_eq!(flat.flatness(), 0.0);
+        assert_eq!(Board::new().flatness(), 0.0);
+        
+        let mut jagged = Board::new();
+        for col in (0..BOARD_WIDTH).step_by(2) {
+            for row in BOARD_HEIGHT - 4..BOARD_HEIGHT {
+                jagged.set_cell(row, col, Cell::Garbage);
+            }
+        }
+        assert!(jagged.flatness() < flat.flatness());
+    }
+    
+    #[test]
+    fn test_validate_floating_clump() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 0, Cell::Garbage);
+        board.set_cell(BOARD_HEIGHT - 2, 0, Cell::Filled(PieceType::I));
+        assert_eq!(board.validate(GravityMode::Sticky), Ok(()));
+        
+        // An O hanging over empty cells is fine under naive gravity only
+        board.set_cell(BOARD_HEIGHT - 5, 4, Cell::Filled(PieceType::O));
+        board.set_cell(BOARD_HEIGHT - 5, 5, Cell::Filled(PieceType::O));
+        board.set_cell(BOARD_HEIGHT - 4, 4, Cell::Filled(PieceType::O));
+        board.set_cell(BOARD_HEIGHT - 4, 5, Cell::Filled(PieceType::O));
+        assert_eq!(board.validate(GravityMode::Naive), Ok(()));
+        assert_eq!(
+            board.validate(GravityMode::Sticky),
+            Err(BoardInvariantError::FloatingClump { row: BOARD_HEIGHT - 5, col: 4 })
+        );
+    }
+
+    #[test]
+    fn test_piece_type_at() {
+        let mut board = Board::new();
+        board.set_cell(BOARD_HEIGHT - 1, 3, Cell::Filled(PieceType::T));
+        board.set_cell(BOARD_HEIGHT - 1, 4, Cell::Garbage);
+        
+        assert_eq!(board.piece_type_at(BOARD_HEIGHT - 1, 3), Some(PieceType::T));
+        assert_eq!(board.piece_type_at(BOARD_HEIGHT - 1, 4), None);
+        assert_eq!(board.piece_type_at(BOARD_HEIGHT - 1, 5), None);
+        assert_eq!(board.piece_type_at(BOARD_HEIGHT, 3), None);
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let board = Board::from_rows(&[
+            "....T.....",
+            "...TTT..#.",
+            "IIII.OO###",
+        ]).unwrap();
+        
+        assert_eq!(board.get_cell(BOARD_HEIGHT - 3, 4), Some(&Cell::Filled(PieceType::T)));
+        assert_eq!(board.get_cell(BOARD_HEIGHT - 2, 8), Some(&Cell::Garbage));
+        assert_eq!(board.get_cell(BOARD_HEIGHT - 1, 0), Some(&Cell::Filled(PieceType::I)));
+        assert_eq!(board.get_cell(BOARD_HEIGHT - 1, 4), Some(&Cell::Empty));
+        assert_eq!(board.filled_count_in_row(BOARD_HEIGHT - 4), 0);
+        
+        assert_eq!(Board::from_rows(&["....", "IIII.OO###"]), Err(BoardError::InvalidRowWidth { row: 0, width: 4 }));
+        assert_eq!(Board::from_rows(&[".........."; BOARD_HEIGHT + 1]), Err(BoardError::TooManyRows(BOARD_HEIGHT + 1)));
+    }
+
+    #[test]
+    fn test_with_dimensions() {
+        assert_eq!(Board::with_dimensions(0, 5), Err(BoardError::InvalidDimensions { width: 0, height: 5 }));
+        assert_eq!(Board::with_dimensions(10, 22), Ok(Board::new()));
+        assert!(matches!(Board::with_dimensions_capped(10, 22, 20), Err(BoardError::InvalidDimensions { .. })));
+        assert!(matches!(Board::with_dimensions(12, 22), Err(BoardError::UnsupportedDimensions { .. })));
+    }
+}
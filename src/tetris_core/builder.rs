@@ -0,0 +1,158 @@
+use std::fmt;
+use std::time::Duration;
+use super::game::{Game, GameConfig, GoalMode};
+use super::randomizer::Randomizer;
+
+/// Errors from building a game with `GameBuilder`
+#[derive(Debug, PartialEq)]
+pub enum BuildError {
+    /// Levels start at 1
+    ZeroStartingLevel,
+    /// A sprint needs at least one line to clear
+    EmptySprintGoal,
+    /// Every piece gravity multiplier has to be a positive number
+    InvalidGravityMultiplier,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::ZeroStartingLevel => write!(f, "starting level must be at least 1"),
+            BuildError::EmptySprintGoal => write!(f, "sprint goal must be at least 1 line"),
+            BuildError::InvalidGravityMultiplier => write!(f, "piece gravity multipliers must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Chained configuration for a new game, checked once in `build`
+/// Anything left unset keeps its `GameConfig::default()` value
+pub struct GameBuilder {
+    config: GameConfig,
+    randomizer: Option<Box<dyn Randomizer>>,
+}
+
+impl GameBuilder {
+    /// Start from the default rules and a fresh 7-bag randomizer
+    pub fn new() -> Self {
+        GameBuilder {
+            config: GameConfig::default(),
+            randomizer: None,
+        }
+    }
+
+    /// Start from an existing set of rules
+    pub fn config(mut self, config: GameConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Level the game starts at
+    pub fn starting_level(mut self, level: u32) -> Self {
+        self.config.starting_level = level;
+        self
+    }
+
+    /// Deal pieces from the given randomizer
+    pub fn randomizer(mut self, randomizer: Box<dyn Randomizer>) -> Self {
+        self.randomizer = Some(randomizer);
+        self
+    }
+
+    /// How long a grounded piece waits before locking
+    pub fn lock_delay(mut self, lock_delay: Duration) -> Self {
+        self.config.lock_delay = lock_delay;
+        self
+    }
+
+    /// Most upcoming pieces the game reveals
+    pub fn preview_length(mut self, length: usize) -> Self {
+        self.config.preview_length = length;
+        self
+    }
+
+    /// What the game is played for
+    pub fn goal(mut self, goal: GoalMode) -> Self {
+        self.config.goal = goal;
+        self
+    }
+
+    /// Check the configuration and create the game
+    pub fn build(self) -> Result<Game, BuildError> {
+        if self.config.starting_level == 0 {
+            return Err(BuildError::ZeroStartingLevel);
+        }
+        if self.config.goal == GoalMode::Sprint(0) {
+            return Err(BuildError::EmptySprintGoal);
+        }
+        if !self.config.piece_gravity_multipliers.iter().all(|&multiplier| multiplier > 0.0) {
+            return Err(BuildError::InvalidGravityMultiplier);
+        }
+
+        Ok(match self.randomizer {
+            Some(randomizer) => Game::with_randomizer(self.config, randomizer),
+            None => Game::with_config(self.config),
+        })
+    }
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tetris_core::{Cell, GameOverReason, GameState, PieceType, Rotation, ScriptedRandomizer, BOARD_HEIGHT};
+
+    #[test]
+    fn test_build() {
+        let script = [PieceType::T, PieceType::I, PieceType::O, PieceType::L];
+        let game = GameBuilder::new()
+            .starting_level(10)
+            .randomizer(Box::new(ScriptedRandomizer::new(&script)))
+            .lock_delay(Duration::from_millis(300))
+            .preview_length(2)
+            .goal(GoalMode::Sprint(40))
+            .build()
+            .unwrap();
+
+        assert_eq!(game.score_system.level, 10);
+        assert_eq!(game.lock_delay(), Duration::from_millis(300));
+        assert_eq!(game.preview_pieces(), vec![PieceType::I, PieceType::O]);
+        assert_eq!(game.peek_next_pieces(3), vec![PieceType::I, PieceType::O, PieceType::L]);
+        assert_eq!(game.config.goal, GoalMode::Sprint(40));
+
+        assert_eq!(GameBuilder::new().starting_level(0).build().err(), Some(BuildError::ZeroStartingLevel));
+        assert_eq!(GameBuilder::new().goal(GoalMode::Sprint(0)).build().err(), Some(BuildError::EmptySprintGoal));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_gravity_multipliers() {
+        for invalid in [0.0, -1.0, f64::NAN] {
+            let mut piece_gravity_multipliers = [1.0; 7];
+            piece_gravity_multipliers[PieceType::I.to_index()] = invalid;
+            let config = GameConfig {
+                piece_gravity_multipliers,
+                ..GameConfig::default()
+            };
+            assert_eq!(GameBuilder::new().config(config).build().err(), Some(BuildError::InvalidGravityMultiplier));
+        }
+    }
+
+    #[test]
+    fn test_sprint_goal_ends_game() {
+        let mut game = GameBuilder::new().goal(GoalMode::Sprint(1)).build().unwrap();
+        let bottom = BOARD_HEIGHT - 1;
+        for col in 4..10 {
+            game.board.set_cell(bottom, col, Cell::Garbage);
+        }
+
+        assert!(game.place_piece_at(PieceType::I, bottom, 1, Rotation::North));
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.game_over_reason(), Some(GameOverReason::GoalReached));
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::board::Board;
+use super::piece::Piece;
+use super::rotation::RotationSystem;
+
+/// Minimum number of inputs (moves and rotations) needed to take a freshly spawned piece
+/// to the position where `target` would land with a hard drop
+/// Returns None if the placement can't be reached without soft dropping
+pub fn optimal_input_count(board: &Board, spawn: &Piece, target: &Piece) -> Option<u32> {
+    let target_blocks = landing_blocks(board, target);
+
+    // Breadth-first search over every position reachable at spawn height
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    visited.insert(state_key(spawn));
+    queue.push_back((spawn.clone(), 0));
+
+    while let Some((piece, inputs)) = queue.pop_front() {
+        if landing_blocks(board, &piece) == target_blocks {
+            return Some(inputs);
+        }
+
+        let shifted = [piece.with_left_move(), piece.with_right_move()]
+            .into_iter()
+            .filter(|moved| board.can_place(moved));
+        let rotated = [
+            RotationSystem::rotate_clockwise(&piece, board),
+            RotationSystem::rotate_counterclockwise(&piece, board),
+        ]
+        .into_iter()
+        .flatten();
+
+        // Wall kicks can lift the piece, so stay at or below spawn height to keep the search finite
+        for next in shifted.chain(rotated).filter(|next| next.row >= spawn.row) {
+            if visited.insert(state_key(&next)) {
+                queue.push_back((next, inputs + 1));
+            }
+        }
+    }
+
+    None
+}
+
+fn state_key(piece: &Piece) -> (usize, i32, i32) {
+    (piece.rotation.to_index(), piece.row, piece.col)
+}
+
+/// Sorted block positions of the piece after a hard drop
+fn landing_blocks(board: &Board, piece: &Piece) -> [(i32, i32); 4] {
+    let mut landed = piece.clone();
+    loop {
+        let moved = landed.with_down_move();
+        if !board.can_place(&moved) {
+            break;
+        }
+        landed = moved;
+    }
+
+    let mut blocks = landed.get_block_positions();
+    blocks.sort();
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::piece::PieceType;
+
+    #[test]
+    fn test_optimal_input_count() {
+        let board = Board::new();
+        let spawn = Piece::new(PieceType::T, 0, 4);
+
+        assert_eq!(optimal_input_count(&board, &spawn, &spawn), Some(0));
+        assert_eq!(optimal_input_count(&board, &spawn, &Piece::new(PieceType::T, 0, 1)), Some(3));
+        assert_eq!(optimal_input_count(&board, &spawn, &spawn.with_clockwise_rotation().with_left_move()), Some(2));
+    }
+}
@@ -1,31 +1,78 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
 use std::time::{Duration, Instant};
-use super::board::Board;
-use super::piece::{Piece, PieceType};
-use super::randomizer::{Randomizer, BagRandomizer};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use super::board::{Board, Cell};
+use super::finesse;
+use super::garbage::GarbageMeter;
+use super::piece::{Piece, PieceType, Rotation};
+use super::randomizer::{Randomizer, BagRandomizer, ScriptedRandomizer};
 use super::rotation::RotationSystem;
+use super::stats::GameStats;
 use super::{BOARD_WIDTH, BOARD_HEIGHT};
 
 /// Represents the current state of the game
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum GameState {
+    Ready, // Counting down before the first piece spawns
     Playing,
     Paused,
     GameOver,
 }
 
+/// Why a game ended
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GameOverReason {
+    BlockOut,          // A new piece couldn't spawn
+    OutOfPieces,       // The randomizer had no more pieces to deal
+    TimeLimit,         // The time limit ran out
+    OpponentToppedOut, // Ended by the opponent's game ending first
+    Forfeit,           // The player gave up or disconnected
+    GoalReached,       // The goal of the mode was met, e.g. 40 lines in Sprint
+}
+
 /// Represents the scoring system for the Tetris game
 pub struct ScoreSystem {
     pub score: u32,
     pub level: u32,
     pub lines_cleared: u32,
+    /// Level the game started at; each 10 lines cleared go up one level from here
+    pub starting_level: u32,
+    /// Whether line clears are scored with the level before the clear (guideline)
+    /// or with the level reached after it
+    pub score_before_level_up: bool,
 }
 
 impl ScoreSystem {
     pub fn new() -> Self {
+        Self::starting_at(1)
+    }
+    
+    /// Create a scoring system that starts at the given level
+    pub fn starting_at(level: u32) -> Self {
         ScoreSystem {
             score: 0,
-            level: 1,
+            level,
             lines_cleared: 0,
+            starting_level: level,
+            score_before_level_up: true,
+        }
+    }
+    
+    /// Record cleared lines and add the line score using the configured level semantics
+    fn award_lines(&mut self, lines: usize, line_multiplier: u32) {
+        if self.score_before_level_up {
+            self.score += line_multiplier * self.level;
+        }
+        
+        self.lines_cleared += lines as u32;
+        
+        // Level up every 10 lines
+        self.level = self.starting_level + self.lines_cleared / 10;
+        
+        if !self.score_before_level_up {
+            self.score += line_multiplier * self.level;
         }
     }
     
@@ -44,11 +91,7 @@ impl ScoreSystem {
             _ => 0,      // Invalid
         };
         
-        self.score += line_multiplier * self.level;
-        self.lines_cleared += lines as u32;
-        
-        // Level up every 10 lines
-        self.level = (self.lines_cleared / 10) + 1;
+        self.award_lines(lines, line_multiplier);
     }
     
     /// Add score based on lines cleared with T-spin bonus
@@ -84,11 +127,7 @@ impl ScoreSystem {
             (_, _) => 0,
         };
         
-        self.score += line_multiplier * self.level;
-        self.lines_cleared += lines as u32;
-        
-        // Level up every 10 lines
-        self.level = (self.lines_cleared / 10) + 1;
+        self.award_lines(lines, line_multiplier);
     }
     
     /// Add score for a perfect clear (all lines cleared from the board)
@@ -124,10 +163,167 @@ pub enum TSpinType {
     Full
 }
 
+/// Which pieces besides the T can score spins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpinRule {
+    /// Only T pieces spin, using the corner rule
+    TSpinOnly,
+    /// Any piece rotated into a spot it can't move out of (up, down, left or right)
+    /// scores a full spin
+    AllImmobile,
+    /// SRS+ style all-spin-mini: like `AllImmobile`, and kicked rotations that leave a
+    /// non-T piece free to move score a mini
+    AllSpinMini,
+}
+
+/// What a game is played for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GoalMode {
+    /// Play until topping out
+    Endless,
+    /// Clear the given number of lines as fast as possible
+    Sprint(u32),
+}
+
+/// Configurable rules for a game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameConfig {
+    /// Maximum number of holds over the whole game (None means unlimited)
+    pub total_hold_limit: Option<u32>,
+    /// Countdown before the first piece spawns ("3-2-1-go"); None starts immediately
+    pub countdown: Option<Duration>,
+    /// Whether hard drop is allowed; classic modes only have soft drop and gravity
+    pub hard_drop_enabled: bool,
+    /// Moves and rotations that may restart the lock delay before the piece is forced to lock
+    pub max_lock_resets: u8,
+    /// Whether pieces fall on their own; puzzle editors turn this off so pieces float
+    /// until moved down or hard dropped
+    pub gravity_enabled: bool,
+    /// How long queued garbage waits on the meter before it can be inserted, leaving
+    /// a window to cancel it with attack; zero inserts it at the next lock
+    pub garbage_delay: Duration,
+    /// How many times faster each piece type falls than the level's gravity, indexed by
+    /// `PieceType::to_index`; entries that aren't positive are treated as 1.0
+    pub piece_gravity_multipliers: [f64; 7],
+    /// Whether moves and rotations blocked by the stack or a wall still restart the lock delay
+    /// (classic "infinity"); otherwise only successful ones do
+    pub reset_lock_on_failed_input: bool,
+    /// Whether a full T-spin needs both corners the T points to filled (guideline), making
+    /// it a mini when one of them is open; otherwise every three-corner T-spin is full
+    pub strict_tspin_corners: bool,
+    /// Entry delay (ARE) between a piece locking and the next one spawning; zero spawns
+    /// the next piece right away
+    pub are: Duration,
+    /// Level the game starts at, which also sets the starting gravity
+    pub starting_level: u32,
+    /// How long a grounded piece waits before locking
+    pub lock_delay: Duration,
+    /// Number of upcoming pieces `preview_pieces` reveals
+    pub preview_length: usize,
+    /// What the game is played for; reaching a goal ends the game
+    pub goal: GoalMode,
+    /// Which pieces besides the T can score spins
+    pub spin_rule: SpinRule,
+    /// Whether locks are checked for finesse faults; each check runs a search of its own,
+    /// so move searches turn it off on the copies they simulate
+    pub track_finesse: bool,
+    /// Whether cells above the top row count as filled when checking spin corners
+    pub solid_ceiling: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            total_hold_limit: None,
+            countdown: None,
+            hard_drop_enabled: true,
+            max_lock_resets: MAX_LOCK_RESETS,
+            gravity_enabled: true,
+            garbage_delay: Duration::ZERO,
+            piece_gravity_multipliers: [1.0; 7],
+            reset_lock_on_failed_input: false,
+            strict_tspin_corners: true,
+            are: Duration::ZERO,
+            starting_level: 1,
+            lock_delay: LOCK_DELAY,
+            preview_length: DEFAULT_PREVIEW_LENGTH,
+            goal: GoalMode::Endless,
+            spin_rule: SpinRule::TSpinOnly,
+            track_finesse: true,
+            solid_ceiling: true,
+        }
+    }
+}
+
+/// Garbage lines sent by a clear, following the guideline attack table
+/// `back_to_back` is whether the clear continues a back-to-back chain of Tetrises and spins,
+/// and `combo` counts the clears in a row before this one (-1 or less for no combo)
+pub fn attack_lines(lines: usize, tspin: TSpinType, back_to_back: bool, combo: i32, perfect_clear: bool) -> usize {
+    if lines == 0 {
+        return 0;
+    }
+    
+    let base = match (lines, tspin) {
+        (1, TSpinType::Full) => 2,
+        (2, TSpinType::Full) => 4,
+        (3, TSpinType::Full) => 6,
+        (2, TSpinType::Mini) => 1,
+        (2, TSpinType::None) => 1,
+        (3, TSpinType::None) => 2,
+        (4, TSpinType::None) => 4,
+        _ => 0,
+    };
+    let back_to_back_bonus = if back_to_back { 1 } else { 0 };
+    let combo_bonus = match usize::try_from(combo) {
+        Ok(combo) => COMBO_ATTACK[combo.min(COMBO_ATTACK.len() - 1)],
+        Err(_) => 0,
+    };
+    let perfect_clear_bonus = if perfect_clear { PERFECT_CLEAR_ATTACK } else { 0 };
+    
+    base + back_to_back_bonus + combo_bonus + perfect_clear_bonus
+}
+
+// Extra garbage by combo count; longer combos send the last value
+const COMBO_ATTACK: [usize; 12] = [0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5];
+
+// Extra garbage for clearing the whole board
+const PERFECT_CLEAR_ATTACK: usize = 10;
+
+// Corners on the pointed side of a T piece, as (row, col) offsets from its center,
+// indexed by rotation (North points down, East right, South up, West left)
+const TSPIN_FRONT_CORNERS: [[(i32, i32); 2]; 4] = [
+    [(1, -1), (1, 1)],
+    [(-1, 1), (1, 1)],
+    [(-1, -1), (-1, 1)],
+    [(-1, -1), (1, -1)],
+];
+
+// Upcoming pieces shown by default
+const DEFAULT_PREVIEW_LENGTH: usize = 5;
+
+// Number of next pieces included by `Game::encode_queue`
+const ENCODED_QUEUE_LENGTH: usize = 5;
+
+// Number of recent placements remembered for the masked ("invisible") board view
+const MAX_TRACKED_PLACEMENTS: usize = 100;
+
 // Lock delay constants
 const LOCK_DELAY: Duration = Duration::from_millis(500); // Standard 0.5s lock delay
 const MAX_LOCK_RESETS: u8 = 15; // Maximum number of lock delay resets
 
+/// A piece of the given type at its spawn position
+fn spawn_piece(piece_type: PieceType) -> Piece {
+    let col = (BOARD_WIDTH as i32 / 2) - 1; // Center position, slightly to the left
+
+    // Adjust initial row position based on piece type
+    let row = match piece_type {
+        PieceType::I => -1, // I needs to start higher
+        _ => 0,
+    };
+
+    Piece::new(piece_type, row, col)
+}
+
 /// The main game controller for Tetris
 pub struct Game {
     pub board: Board,
@@ -136,7 +332,11 @@ pub struct Game {
     pub can_hold: bool,
     pub state: GameState,
     pub score_system: ScoreSystem,
+    pub stats: GameStats,
+    pub config: GameConfig,
     randomizer: Box<dyn Randomizer>,
+    // The randomizer as it was when play started, so `retry` can deal the same pieces again
+    starting_randomizer: Option<Box<dyn Randomizer>>,
     time_since_last_drop: Duration,
     gravity_delay: Duration,
     // Lock delay fields
@@ -144,43 +344,231 @@ pub struct Game {
     lock_delay_active: bool,
     lock_delay_resets: u8,
     last_successful_movement: Instant,
+    // Number of holds performed over the whole game
+    holds_used: u32,
+    // Set when the last successful action was a rotation, recording whether it used a kick
+    last_rotation_kick: Option<bool>,
+    // Garbage lines sent by opponents, counting down until they can be inserted
+    garbage_meter: GarbageMeter,
+    // Picks the gap column of inserted garbage rows
+    garbage_rng: StdRng,
+    // Seed to restart `garbage_rng` from on reset, if the game is seeded
+    garbage_seed: Option<u64>,
+    // Whether the last line clear was a Tetris or a spin, so the next one earns back-to-back
+    back_to_back: bool,
+    // Line clears in a row minus one; -1 when the last lock cleared nothing
+    combo: i32,
+    // Cells of the most recently locked pieces, newest last, kept in sync with line clears
+    recent_placements: VecDeque<Vec<(usize, usize)>>,
+    // Time left before play starts while counting down in the Ready state
+    countdown_remaining: Option<Duration>,
+    // Moves and rotations used on the current piece since it spawned
+    placement_inputs: u32,
+    // Set once the game is over
+    game_over_reason: Option<GameOverReason>,
+    // Set by a "stop" power-up; gravity and lock delay halt while the game keeps playing
+    frozen: bool,
+    // Placements since the last one that cleared a line (the "drought")
+    pieces_since_clear: u32,
+    // Time left in the entry delay before the next piece spawns, while it's running
+    are_remaining: Option<Duration>,
+    // Orientation and hold requested during the entry delay (IRS/IHS), applied at the next spawn
+    buffered_rotation: Option<Rotation>,
+    buffered_hold: bool,
+    // Spin detected for the most recently locked piece
+    last_spin: TSpinType,
+    // Height of the topmost block of the most recently locked piece, before any line clear
+    last_landing_height: Option<usize>,
+    // Garbage the most recent lock sent, before it was used to cancel incoming garbage
+    last_attack: usize,
 }
 
 impl Game {
     /// Create a new Tetris game
     pub fn new() -> Self {
-        let mut game = Game {
+        Self::with_config(GameConfig::default())
+    }
+    
+    /// Create a new Tetris game using the given rules
+    pub fn with_config(config: GameConfig) -> Self {
+        let mut game = Self::unstarted(config);
+        game.begin();
+        game
+    }
+    
+    /// Create a new Tetris game that deals its pieces from the given randomizer
+    pub fn with_randomizer(config: GameConfig, randomizer: Box<dyn Randomizer>) -> Self {
+        let mut game = Self::unstarted(config);
+        game.randomizer = randomizer;
+        game.begin();
+        game
+    }
+    
+    /// Create a game for a versus match; every game built from the same match seed
+    /// deals the same piece sequence, so both players get identical bags, and places
+    /// the gaps of received garbage the same way
+    pub fn new_match(seed: u64) -> Self {
+        Self::new_match_with_config(seed, GameConfig::default())
+    }
+    
+    /// Like `new_match`, playing by the given rules
+    pub fn new_match_with_config(seed: u64, config: GameConfig) -> Self {
+        let mut game = Self::with_randomizer(config, Box::new(BagRandomizer::with_seed(seed)));
+        game.garbage_seed = Some(seed);
+        game.garbage_rng = StdRng::seed_from_u64(seed);
+        game
+    }
+    
+    /// Create a game with default rules that waits for `start` before spawning a piece
+    /// Useful to set up a board first without wasting the first piece of the bag
+    pub fn new_empty() -> Self {
+        Self::unstarted(GameConfig::default())
+    }
+    
+    /// Build a game in the Ready state without a current piece
+    fn unstarted(config: GameConfig) -> Self {
+        Game {
             board: Board::new(),
             current_piece: None,
             held_piece: None,
             can_hold: true,
-            state: GameState::Playing,
-            score_system: ScoreSystem::new(),
+            state: GameState::Ready,
+            score_system: ScoreSystem::starting_at(config.starting_level),
+            stats: GameStats::new(),
+            config,
             randomizer: Box::new(BagRandomizer::new()),
+            starting_randomizer: None,
             time_since_last_drop: Duration::ZERO,
-            gravity_delay: Duration::from_millis(1000), // Initial gravity speed
+            gravity_delay: Self::calculate_gravity_delay(config.starting_level),
             // Initialize lock delay fields
             lock_delay_timer: Duration::ZERO,
             lock_delay_active: false,
             lock_delay_resets: 0,
             last_successful_movement: Instant::now(),
-        };
+            holds_used: 0,
+            last_rotation_kick: None,
+            garbage_meter: GarbageMeter::new(),
+            garbage_rng: StdRng::from_entropy(),
+            garbage_seed: None,
+            back_to_back: false,
+            combo: -1,
+            recent_placements: VecDeque::new(),
+            countdown_remaining: None,
+            placement_inputs: 0,
+            game_over_reason: None,
+            frozen: false,
+            pieces_since_clear: 0,
+            are_remaining: None,
+            buffered_rotation: None,
+            buffered_hold: false,
+            last_spin: TSpinType::None,
+            last_landing_height: None,
+            last_attack: 0,
+        }
+    }
+    
+    /// Start the configured countdown, or start playing right away
+    fn begin(&mut self) {
+        match self.config.countdown {
+            Some(countdown) if !countdown.is_zero() => {
+                self.state = GameState::Ready;
+                self.countdown_remaining = Some(countdown);
+            }
+            _ => {
+                self.state = GameState::Ready;
+                self.start();
+            }
+        }
+    }
+    
+    /// Leave the Ready state and spawn the first piece
+    /// Returns false if the game had already started
+    pub fn start(&mut self) -> bool {
+        if self.state != GameState::Ready {
+            return false;
+        }
+        
+        self.state = GameState::Playing;
+        self.countdown_remaining = None;
+        self.starting_randomizer = Some(self.randomizer.clone_box());
         
         // Spawn the first piece
-        game.spawn_new_piece();
+        self.spawn_new_piece();
+        true
+    }
+    
+    /// Create a new game whose bottom `rows` rows are filled with "cheese" garbage
+    /// Each row has a single gap, and consecutive rows never share a gap column
+    /// The seed also fixes the piece sequence, as with `new_match`
+    pub fn start_with_cheese(rows: usize, seed: u64) -> Self {
+        let mut game = Self::new_match(seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        
+        let mut last_gap = None;
+        for _ in 0..rows.min(BOARD_HEIGHT) {
+            let mut gap_col = rng.gen_range(0..BOARD_WIDTH);
+            while Some(gap_col) == last_gap {
+                gap_col = rng.gen_range(0..BOARD_WIDTH);
+            }
+            game.board.insert_garbage_rows(1, gap_col);
+            last_gap = Some(gap_col);
+        }
+        
+        // Too much cheese can leave no room for the first piece
+        if game.current_piece.as_ref().is_some_and(|piece| !game.board.can_place(piece)) {
+            game.force_game_over(GameOverReason::BlockOut);
+        }
         
         game
     }
     
     /// Update the game state based on elapsed time
     pub fn update(&mut self, dt: Duration) -> bool {
+        // Count down before play starts (a game without a countdown waits for `start`)
+        if self.state == GameState::Ready {
+            return match self.countdown_remaining {
+                Some(remaining) if dt >= remaining => self.start(),
+                Some(remaining) => {
+                    self.countdown_remaining = Some(remaining - dt);
+                    true
+                }
+                None => false,
+            };
+        }
+        
         if self.state != GameState::Playing {
             return false;
         }
         
-        // Apply gravity
+        self.garbage_meter.tick(dt);
+        
+        // A frozen game stays in play, but the piece neither falls nor locks
+        if self.frozen {
+            return true;
+        }
+        
+        // No piece falls during the entry delay; spawn the next one once it runs out
+        if let Some(remaining) = self.are_remaining {
+            if dt >= remaining {
+                self.are_remaining = None;
+                self.spawn_buffered_piece();
+            } else {
+                self.are_remaining = Some(remaining - dt);
+            }
+            return true;
+        }
+        
+        // Apply gravity, scaled for the type of the falling piece
         self.time_since_last_drop += dt;
-        if self.time_since_last_drop >= self.gravity_delay {
+        // Multipliers that aren't positive numbers fall back to the level's gravity, and
+        // ones too small to represent leave the piece hanging
+        let gravity_multiplier = self.current_piece.as_ref()
+            .map(|piece| self.config.piece_gravity_multipliers[piece.piece_type.to_index()])
+            .filter(|&multiplier| multiplier > 0.0)
+            .unwrap_or(1.0);
+        let gravity_delay = Duration::try_from_secs_f64(self.gravity_delay.as_secs_f64() / gravity_multiplier)
+            .unwrap_or(Duration::MAX);
+        if self.config.gravity_enabled && self.time_since_last_drop >= gravity_delay {
             self.time_since_last_drop = Duration::ZERO;
             
             // Try to move piece down
@@ -188,6 +576,7 @@ impl Game {
                 let moved_piece = current_piece.with_down_move();
                 if self.board.can_place(&moved_piece) {
                     self.current_piece = Some(moved_piece);
+                    self.last_rotation_kick = None;
                     // Reset lock delay when piece moves down successfully
                     self.lock_delay_active = false;
                     self.lock_delay_timer = Duration::ZERO;
@@ -205,7 +594,7 @@ impl Game {
         // Process lock delay
         if self.lock_delay_active {
             self.lock_delay_timer += dt;
-            if self.lock_delay_timer >= LOCK_DELAY {
+            if self.lock_delay_timer >= self.config.lock_delay {
                 // Lock delay expired, lock the piece
                 self.lock_piece();
                 self.lock_delay_active = false;
@@ -216,50 +605,81 @@ impl Game {
         true
     }
     
+    /// How long a grounded piece waits before locking
+    pub fn lock_delay(&self) -> Duration {
+        self.config.lock_delay
+    }
+    
     /// Attempt to reset lock delay when the player moves or rotates
     fn try_reset_lock_delay(&mut self) {
-        if self.lock_delay_active && self.lock_delay_resets < MAX_LOCK_RESETS {
+        if self.lock_delay_active && self.lock_delay_resets < self.config.max_lock_resets {
             self.lock_delay_timer = Duration::ZERO;
             self.lock_delay_resets += 1;
         }
     }
     
+    /// Restart the lock delay for a move or rotation that was blocked, if the rules say so
+    fn on_failed_input(&mut self) {
+        if self.config.reset_lock_on_failed_input && self.current_piece.is_some() {
+            self.try_reset_lock_delay();
+        }
+    }
+    
     /// Move the current piece left if possible
     pub fn move_left(&mut self) -> bool {
+        if self.frozen {
+            return false;
+        }
+        
         if let Some(ref current_piece) = self.current_piece {
             let moved_piece = current_piece.with_left_move();
             if self.board.can_place(&moved_piece) {
                 self.current_piece = Some(moved_piece);
+                self.placement_inputs += 1;
                 self.last_successful_movement = Instant::now();
+                self.last_rotation_kick = None;
                 self.try_reset_lock_delay();
                 return true;
             }
         }
+        self.on_failed_input();
         false
     }
     
     /// Move the current piece right if possible
     pub fn move_right(&mut self) -> bool {
+        if self.frozen {
+            return false;
+        }
+        
         if let Some(ref current_piece) = self.current_piece {
             let moved_piece = current_piece.with_right_move();
             if self.board.can_place(&moved_piece) {
                 self.current_piece = Some(moved_piece);
+                self.placement_inputs += 1;
                 self.last_successful_movement = Instant::now();
+                self.last_rotation_kick = None;
                 self.try_reset_lock_delay();
                 return true;
             }
         }
+        self.on_failed_input();
         false
     }
     
     /// Move the current piece down if possible, lock if not
     pub fn move_down(&mut self) -> bool {
+        if self.frozen {
+            return false;
+        }
+        
         if let Some(ref current_piece) = self.current_piece {
             let moved_piece = current_piece.with_down_move();
             if self.board.can_place(&moved_piece) {
                 self.score_system.add_soft_drop_score(1);
                 self.current_piece = Some(moved_piece);
                 self.last_successful_movement = Instant::now();
+                self.last_rotation_kick = None;
                 return true;
             } else if !self.lock_delay_active {
                 // Start lock delay
@@ -272,48 +692,79 @@ impl Game {
     }
     
     /// Rotate the current piece clockwise if possible
+    /// During the entry delay the rotation is buffered for the next piece instead (IRS)
     pub fn rotate_clockwise(&mut self) -> bool {
+        if self.frozen {
+            return false;
+        }
+        
+        if self.is_in_are() {
+            self.buffered_rotation = Some(self.buffered_rotation.unwrap_or(Rotation::North).rotate_cw());
+            return true;
+        }
+        
         if let Some(ref current_piece) = self.current_piece {
             if let Some(rotated_piece) = RotationSystem::rotate_clockwise(current_piece, &self.board) {
+                // The first kick test is the unshifted position, so any shift means a kick was used
+                let kicked = rotated_piece.row != current_piece.row || rotated_piece.col != current_piece.col;
+                self.last_rotation_kick = Some(kicked);
                 self.current_piece = Some(rotated_piece);
+                self.placement_inputs += 1;
                 self.last_successful_movement = Instant::now();
                 self.try_reset_lock_delay();
                 return true;
             }
         }
+        self.on_failed_input();
         false
     }
     
     /// Rotate the current piece counter-clockwise if possible
+    /// During the entry delay the rotation is buffered for the next piece instead (IRS)
     pub fn rotate_counterclockwise(&mut self) -> bool {
+        if self.frozen {
+            return false;
+        }
+        
+        if self.is_in_are() {
+            self.buffered_rotation = Some(self.buffered_rotation.unwrap_or(Rotation::North).rotate_ccw());
+            return true;
+        }
+        
         if let Some(ref current_piece) = self.current_piece {
             if let Some(rotated_piece) = RotationSystem::rotate_counterclockwise(current_piece, &self.board) {
+                // The first kick test is the unshifted position, so any shift means a kick was used
+                let kicked = rotated_piece.row != current_piece.row || rotated_piece.col != current_piece.col;
+                self.last_rotation_kick = Some(kicked);
                 self.current_piece = Some(rotated_piece);
+                self.placement_inputs += 1;
                 self.last_successful_movement = Instant::now();
                 self.try_reset_lock_delay();
                 return true;
             }
         }
+        self.on_failed_input();
         false
     }
     
     /// Perform a hard drop, instantly placing the piece at the lowest possible position
     pub fn hard_drop(&mut self) -> bool {
-        if let Some(mut piece) = self.current_piece.take() {
-            let mut drop_distance = 0;
-            
-            // Move down until collision
-            loop {
-                let moved_piece = piece.with_down_move();
-                if !self.board.can_place(&moved_piece) {
-                    break;
-                }
-                piece = moved_piece;
-                drop_distance += 1;
-            }
-            
+        self.hard_drop_scored(true)
+    }
+    
+    /// Hard drop, adding the drop distance points only if `award_points` is set
+    /// Line clear points are awarded either way; RL setups that shape their own reward
+    /// can leave the drop points out of the score
+    pub fn hard_drop_scored(&mut self, award_points: bool) -> bool {
+        if self.frozen || !self.config.hard_drop_enabled {
+            return false;
+        }
+        
+        if let Some((piece, drop_distance)) = self.landing() {
             // Add score for the drop
-            self.score_system.add_hard_drop_score(drop_distance);
+            if award_points {
+                self.score_system.add_hard_drop_score(drop_distance as u32);
+            }
             
             // Place the piece
             self.current_piece = Some(piece);
@@ -323,9 +774,81 @@ impl Game {
         false
     }
     
+    /// Lock a piece straight onto the board at `(row, col)` with `rotation`, for building
+    /// puzzles and test setups without steering the current piece there
+    /// Lines are cleared and scored and the piece counted as a regular lock would, but the
+    /// current piece is left alone. Returns false if the piece would overlap filled cells,
+    /// the current piece or leave the board
+    pub fn place_piece_at(&mut self, piece_type: PieceType, row: usize, col: usize, rotation: Rotation) -> bool {
+        let mut piece = Piece::new(piece_type, row as i32, col as i32);
+        piece.rotation = rotation;
+        if !self.board.can_place(&piece) {
+            return false;
+        }
+        if let Some(ref current_piece) = self.current_piece {
+            let current_blocks = current_piece.get_block_positions();
+            if piece.get_block_positions().iter().any(|block| current_blocks.contains(block)) {
+                return false;
+            }
+        }
+        
+        self.stats.pieces_placed += 1;
+        self.settle_piece(&piece, TSpinType::None);
+        true
+    }
+    
+    /// Replace the current piece with a fresh piece of the given type at its spawn position,
+    /// for setting up scenarios deterministically; the queue and hold are left alone
+    /// Returns false, keeping the current piece, if the new one doesn't fit
+    pub fn set_current_piece(&mut self, piece_type: PieceType) -> bool {
+        let piece = spawn_piece(piece_type);
+        if !self.board.can_place(&piece) {
+            return false;
+        }
+        
+        self.current_piece = Some(piece);
+        self.last_rotation_kick = None;
+        self.placement_inputs = 0;
+        self.lock_delay_active = false;
+        self.lock_delay_timer = Duration::ZERO;
+        true
+    }
+    
+    /// Drop the current piece unplayed and deal the next one from the queue, ending any
+    /// entry delay first, so searches can look past the current piece
+    pub fn deal_next_piece(&mut self) {
+        if self.state != GameState::Playing {
+            return;
+        }
+        
+        self.current_piece = None;
+        self.are_remaining = None;
+        self.lock_delay_active = false;
+        self.lock_delay_timer = Duration::ZERO;
+        self.spawn_buffered_piece();
+    }
+    
     /// Hold the current piece and replace with next or held piece
+    /// With the hold slot empty, the replacement is dealt from the front of the next queue
+    /// exactly like a regular spawn, so the queue advances by one (see `hold_consumes_queue`)
     pub fn hold_piece(&mut self) -> bool {
-        if !self.can_hold {
+        if self.frozen || !self.can_hold {
+            return false;
+        }
+        
+        // Respect the game-wide hold limit, if any
+        if self.config.total_hold_limit.is_some_and(|limit| self.holds_used >= limit) {
+            return false;
+        }
+        
+        // During the entry delay the hold happens as soon as the next piece spawns (IHS)
+        if self.is_in_are() {
+            self.buffered_hold = true;
+            return true;
+        }
+        
+        // Holding into an empty slot needs a next piece to take over
+        if self.held_piece.is_none() && self.randomizer.peek(1).is_empty() {
             return false;
         }
         
@@ -334,12 +857,9 @@ impl Game {
             
             // If we already have a held piece, swap them
             if let Some(held_type) = self.held_piece {
-                let col = (BOARD_WIDTH as i32 / 2) - 1;
-                let row = match held_type {
-                    PieceType::I => -1,
-                    _ => 0,
-                };
-                self.current_piece = Some(Piece::new(held_type, row, col));
+                self.current_piece = Some(spawn_piece(held_type));
+                self.last_rotation_kick = None;
+                self.placement_inputs = 0;
             } else {
                 // Otherwise, spawn a new piece
                 self.spawn_new_piece();
@@ -348,130 +868,121 @@ impl Game {
             // Update the held piece
             self.held_piece = Some(current_type);
             self.can_hold = false;
+            self.holds_used += 1;
             return true;
         }
         
         false
     }
     
-    /// Detect T-spins based on the T piece position and the corners
-    fn detect_tspin(&self) -> TSpinType {
-        if let Some(ref piece) = self.current_piece {
-            if piece.piece_type == PieceType::T {
-                // Get the 4 corners around the T piece center
-                let (row, col) = (piece.row as usize, piece.col as usize);
-                let corners = [
-                    (row - 1, col - 1), // Top-left
-                    (row - 1, col + 1), // Top-right
-                    (row + 1, col - 1), // Bottom-left
-                    (row + 1, col + 1), // Bottom-right
-                ];
-                
-                // Count filled corners
-                let mut filled_corners = 0;
-                for &(r, c) in &corners {
-                    if r < BOARD_HEIGHT && c < BOARD_WIDTH {
-                        if let Some(cell) = self.board.get_cell(r, c) {
-                            if *cell != super::board::Cell::Empty {
-                                filled_corners += 1;
-                            }
-                        } else {
-                            // Out of bounds is considered filled
-                            filled_corners += 1;
-                        }
-                    } else {
-                        // Out of bounds is considered filled
-                        filled_corners += 1;
-                    }
-                }
-                
-                // Detect T-spin types
-                if filled_corners >= 3 {
-                    // Check the front corners based on rotation to determine mini vs full T-spin
-                    match piece.rotation {
-                        super::piece::Rotation::North => {
-                            let front_corners_filled = 
-                                (self.is_cell_filled(row + 1, col - 1) as u8) +
-                                (self.is_cell_filled(row + 1, col + 1) as u8);
-                            if front_corners_filled >= 1 {
-                                return TSpinType::Full;
-                            } else {
-                                return TSpinType::Mini;
-                            }
-                        },
-                        super::piece::Rotation::East => {
-                            let front_corners_filled = 
-                                (self.is_cell_filled(row - 1, col - 1) as u8) +
-                                (self.is_cell_filled(row + 1, col - 1) as u8);
-                            if front_corners_filled >= 1 {
-                                return TSpinType::Full;
-                            } else {
-                                return TSpinType::Mini;
-                            }
-                        },
-                        super::piece::Rotation::South => {
-                            let front_corners_filled = 
-                                (self.is_cell_filled(row - 1, col - 1) as u8) +
-                                (self.is_cell_filled(row - 1, col + 1) as u8);
-                            if front_corners_filled >= 1 {
-                                return TSpinType::Full;
-                            } else {
-                                return TSpinType::Mini;
-                            }
-                        },
-                        super::piece::Rotation::West => {
-                            let front_corners_filled = 
-                                (self.is_cell_filled(row - 1, col + 1) as u8) +
-                                (self.is_cell_filled(row + 1, col + 1) as u8);
-                            if front_corners_filled >= 1 {
-                                return TSpinType::Full;
-                            } else {
-                                return TSpinType::Mini;
-                            }
-                        }
-                    }
-                }
-            }
+    /// The piece that would become current if the player held now
+    /// None if holding isn't allowed right now
+    pub fn piece_after_hold(&self) -> Option<PieceType> {
+        if !self.can_hold || self.current_piece.is_none() {
+            return None;
+        }
+        
+        if self.config.total_hold_limit.is_some_and(|limit| self.holds_used >= limit) {
+            return None;
+        }
+        
+        // An empty hold slot pulls the next piece from the queue instead
+        self.held_piece.or_else(|| self.randomizer.peek(1).first().copied())
+    }
+    
+    /// Whether the next queue piece would fit at its spawn position on the current board
+    /// Lines the current piece might clear aren't taken into account; false if the queue is empty
+    pub fn can_spawn_next(&self) -> bool {
+        self.randomizer.peek(1).first()
+            .is_some_and(|&piece_type| self.board.can_place(&spawn_piece(piece_type)))
+    }
+    
+    /// Detect spins for the current piece, using the T-spin corner rule for T pieces
+    /// and the immobility and kick checks of `config.spin_rule` for other pieces
+    fn detect_spin(&self) -> TSpinType {
+        let piece = match self.current_piece {
+            Some(ref piece) => piece,
+            None => return TSpinType::None,
+        };
+        
+        if piece.piece_type == PieceType::T {
+            return self.detect_tspin();
+        }
+        
+        // Other pieces only spin if their last action was a rotation
+        if self.last_rotation_kick.is_none() {
+            return TSpinType::None;
+        }
+        
+        match self.config.spin_rule {
+            SpinRule::TSpinOnly => TSpinType::None,
+            // Wedged in place: a full spin regardless of the kick used
+            SpinRule::AllImmobile | SpinRule::AllSpinMini if self.is_immobile(piece) => TSpinType::Full,
+            // Kicked into place but still free to move: only a mini
+            SpinRule::AllSpinMini if self.last_rotation_kick == Some(true) => TSpinType::Mini,
+            SpinRule::AllImmobile | SpinRule::AllSpinMini => TSpinType::None,
         }
-        TSpinType::None
     }
     
-    // Helper function to check if a cell is filled or out of bounds
-    fn is_cell_filled(&self, row: usize, col: usize) -> bool {
-        if row >= BOARD_HEIGHT || col >= BOARD_WIDTH {
-            return true; // Out of bounds is considered filled
+    /// Checks whether a piece can't move up, down, left or right
+    fn is_immobile(&self, piece: &Piece) -> bool {
+        let mut up = piece.clone();
+        up.row -= 1;
+        
+        !self.board.can_place(&up) &&
+        !self.board.can_place(&piece.with_down_move()) &&
+        !self.board.can_place(&piece.with_left_move()) &&
+        !self.board.can_place(&piece.with_right_move())
+    }
+    
+    /// Detect T-spins based on the T piece position and the corners
+    fn detect_tspin(&self) -> TSpinType {
+        let piece = match self.current_piece {
+            Some(ref piece) if piece.piece_type == PieceType::T => piece,
+            _ => return TSpinType::None,
+        };
+        let (row, col) = (piece.row, piece.col);
+        let is_filled = |&(row_offset, col_offset): &(i32, i32)| {
+            self.board.is_filled_or_wall(row + row_offset, col + col_offset, self.config.solid_ceiling)
+        };
+        
+        // Count filled corners around the T piece center (out of bounds is considered filled)
+        let corners = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+        if corners.iter().filter(|corner| is_filled(corner)).count() < 3 {
+            return TSpinType::None;
         }
-        match self.board.get_cell(row, col) {
-            Some(cell) if *cell != super::board::Cell::Empty => true,
-            _ => false
+        
+        // The front corners, on the side the T points to, decide mini vs full
+        let front_corners_filled = TSPIN_FRONT_CORNERS[piece.rotation as usize].iter()
+            .filter(|corner| is_filled(corner))
+            .count();
+        let required = if self.config.strict_tspin_corners { 2 } else { 1 };
+        if front_corners_filled >= required {
+            TSpinType::Full
+        } else {
+            TSpinType::Mini
         }
     }
     
     /// Lock the current piece in place and handle line clears
     fn lock_piece(&mut self) {
+        // Check for a spin while the piece is still the current piece
+        let tspin_type = self.detect_spin();
+        
         if let Some(piece) = self.current_piece.take() {
-            // Check for T-spin before placing the piece
-            let tspin_type = self.detect_tspin();
-            
-            // Lock the piece on the board
-            self.board.place_piece(&piece);
-            
-            // Clear completed lines
-            let lines_cleared = self.board.clear_lines();
-            
-            // Check for perfect clear after lines are cleared
-            let is_perfect_clear = lines_cleared > 0 && self.board.is_perfect_clear();
-            
-            // Add score based on the clear type (include t-spin bonus)
-            self.score_system.add_score_for_lines_with_tspin(lines_cleared, tspin_type);
-            
-            // Add perfect clear bonus if achieved
-            if is_perfect_clear {
-                self.score_system.add_perfect_clear_bonus(lines_cleared);
+            // Compare the inputs used against the finesse-optimal route before the board changes
+            if self.config.track_finesse {
+                let optimal_inputs = finesse::optimal_input_count(&self.board, &spawn_piece(piece.piece_type), &piece);
+                if optimal_inputs.is_some_and(|optimal| self.placement_inputs > optimal) {
+                    self.stats.finesse_faults += 1;
+                }
             }
+            self.stats.pieces_placed += 1;
             
-            // Update gravity based on level
-            self.gravity_delay = Self::calculate_gravity_delay(self.score_system.level);
+            self.settle_piece(&piece, tspin_type);
+            if self.state != GameState::Playing {
+                return;
+            }
             
             // Allow holding again
             self.can_hold = true;
@@ -480,8 +991,108 @@ impl Game {
             self.lock_delay_active = false;
             self.lock_delay_timer = Duration::ZERO;
             
-            // Spawn the next piece
-            self.spawn_new_piece();
+            // Spawn the next piece, after the entry delay if there is one
+            if self.config.are.is_zero() {
+                self.spawn_new_piece();
+            } else {
+                self.are_remaining = Some(self.config.are);
+            }
+        }
+    }
+    
+    /// Lock a piece onto the board, clear any lines it completes, score the clear
+    /// and exchange garbage
+    fn settle_piece(&mut self, piece: &Piece, tspin_type: TSpinType) {
+        self.last_spin = tspin_type;
+        self.last_landing_height = piece.get_blocks().iter()
+            .map(|&(row, _)| BOARD_HEIGHT - row)
+            .max();
+        
+        // Lock the piece on the board
+        self.board.place_piece(piece);
+        self.track_placement(piece.get_blocks());
+        
+        // Clear completed lines
+        let full_rows: Vec<usize> = (0..BOARD_HEIGHT)
+            .filter(|&row| self.board.is_line_complete(row))
+            .collect();
+        let lines_cleared = self.board.clear_lines();
+        self.stats.record_clear(lines_cleared);
+        if lines_cleared > 0 {
+            self.pieces_since_clear = 0;
+        } else {
+            self.pieces_since_clear += 1;
+        }
+        self.shift_tracked_placements(|row| {
+            if full_rows.contains(&row) {
+                None
+            } else {
+                // Rows drop by the number of cleared rows beneath them
+                Some(row + full_rows.iter().filter(|&&full_row| full_row > row).count())
+            }
+        });
+        
+        // Check for perfect clear after lines are cleared
+        let is_perfect_clear = lines_cleared > 0 && self.board.is_perfect_clear();
+        
+        // Add score based on the clear type (include t-spin bonus)
+        self.score_system.add_score_for_lines_with_tspin(lines_cleared, tspin_type);
+        
+        // Add perfect clear bonus if achieved
+        if is_perfect_clear {
+            self.score_system.add_perfect_clear_bonus(lines_cleared);
+        }
+        
+        // Tetrises and spins that clear lines keep a back-to-back chain going; other clears
+        // break it. Any clear extends the combo, and a lock without one ends it
+        let difficult = lines_cleared == 4 || (lines_cleared > 0 && tspin_type != TSpinType::None);
+        let back_to_back = difficult && self.back_to_back;
+        if lines_cleared > 0 {
+            self.back_to_back = difficult;
+            self.combo += 1;
+        } else {
+            self.combo = -1;
+        }
+        
+        // Our attack cancels pending garbage first; lines past their delay are inserted now
+        let attack = attack_lines(lines_cleared, tspin_type, back_to_back, self.combo, is_perfect_clear);
+        self.stats.attack_sent += attack as u32;
+        self.last_attack = attack;
+        self.garbage_meter.cancel(attack);
+        let inserted = self.garbage_meter.take_ready();
+        if inserted > 0 {
+            let gap_col = self.garbage_rng.gen_range(0..BOARD_WIDTH);
+            self.board.insert_garbage_rows(inserted, gap_col);
+            self.shift_tracked_placements(|row| row.checked_sub(inserted));
+        }
+        
+        // Update gravity based on level
+        self.gravity_delay = Self::calculate_gravity_delay(self.score_system.level);
+        
+        let goal_reached = match self.config.goal {
+            GoalMode::Endless => false,
+            GoalMode::Sprint(goal_lines) => self.score_system.lines_cleared >= goal_lines,
+        };
+        if goal_reached {
+            self.force_game_over(GameOverReason::GoalReached);
+        }
+    }
+    
+    /// Remember the cells of a newly locked piece
+    fn track_placement(&mut self, cells: Vec<(usize, usize)>) {
+        self.recent_placements.push_back(cells);
+        if self.recent_placements.len() > MAX_TRACKED_PLACEMENTS {
+            self.recent_placements.pop_front();
+        }
+    }
+    
+    /// Move the remembered placement cells to follow rows shifting on the board
+    /// `new_row` maps an old row to its new index, or None if the row is gone
+    fn shift_tracked_placements(&mut self, new_row: impl Fn(usize) -> Option<usize>) {
+        for cells in self.recent_placements.iter_mut() {
+            *cells = cells.iter()
+                .filter_map(|&(row, col)| new_row(row).map(|row| (row, col)))
+                .collect();
         }
     }
     
@@ -510,23 +1121,77 @@ impl Game {
     }
     
     /// Reset the game to its initial state
+    /// Seeded randomizers replay their sequence, unseeded ones deal a fresh one
     pub fn reset(&mut self) {
+        self.randomizer.reset();
+        self.restart();
+    }
+    
+    /// Reset the game but deal exactly the same pieces as the last run, seeded or not,
+    /// so a player can practice the same sequence again
+    pub fn retry(&mut self) {
+        match self.starting_randomizer {
+            Some(ref starting_randomizer) => self.randomizer = starting_randomizer.clone_box(),
+            None => self.randomizer.reset(),
+        }
+        self.restart();
+    }
+    
+    /// Clear the board, score and all per-run state and begin again with the current randomizer
+    fn restart(&mut self) {
         self.board.clear();
         self.current_piece = None;
         self.held_piece = None;
         self.can_hold = true;
-        self.state = GameState::Playing;
-        self.score_system = ScoreSystem::new();
-        self.randomizer = Box::new(BagRandomizer::new());
+        self.score_system = ScoreSystem {
+            score_before_level_up: self.score_system.score_before_level_up,
+            ..ScoreSystem::starting_at(self.config.starting_level)
+        };
         self.time_since_last_drop = Duration::ZERO;
-        self.gravity_delay = Duration::from_millis(1000);
+        self.gravity_delay = Self::calculate_gravity_delay(self.config.starting_level);
         self.lock_delay_active = false;
         self.lock_delay_timer = Duration::ZERO;
         self.lock_delay_resets = 0;
         self.last_successful_movement = Instant::now();
+        self.holds_used = 0;
+        self.garbage_meter.clear();
+        self.garbage_rng = match self.garbage_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.back_to_back = false;
+        self.combo = -1;
+        self.recent_placements.clear();
+        self.stats = GameStats::new();
+        self.game_over_reason = None;
+        self.frozen = false;
+        self.pieces_since_clear = 0;
+        self.are_remaining = None;
+        self.buffered_rotation = None;
+        self.buffered_hold = false;
+        self.last_spin = TSpinType::None;
+        self.last_landing_height = None;
+        self.last_attack = 0;
         
-        // Spawn the first piece
-        self.spawn_new_piece();
+        self.begin();
+    }
+    
+    /// End the game immediately, e.g. when a netplay or tournament controller calls it
+    pub fn force_game_over(&mut self, reason: GameOverReason) {
+        self.state = GameState::GameOver;
+        self.current_piece = None;
+        self.lock_delay_active = false;
+        self.game_over_reason = Some(reason);
+    }
+    
+    /// Placements since the last one that cleared a line (0 right after a clear)
+    pub fn pieces_since_clear(&self) -> u32 {
+        self.pieces_since_clear
+    }
+    
+    /// Why the game ended, or None while it's still going
+    pub fn game_over_reason(&self) -> Option<GameOverReason> {
+        self.game_over_reason
     }
     
     /// Pause or unpause the game
@@ -534,37 +1199,347 @@ impl Game {
         self.state = match self.state {
             GameState::Playing => GameState::Paused,
             GameState::Paused => GameState::Playing,
+            GameState::Ready => GameState::Ready, // Nothing to pause during the countdown
             GameState::GameOver => GameState::GameOver, // Can't unpause game over
         };
     }
     
-    /// Spawns a new piece at the top of the board
-    fn spawn_new_piece(&mut self) {
-        let piece_type = self.randomizer.next();
-        let col = (BOARD_WIDTH as i32 / 2) - 1; // Center position, slightly to the left
-
-        // Adjust initial row position based on piece type
-        let row = match piece_type {
-            PieceType::I => -1, // I needs to start higher
-            _ => 0,
-        };
-
-        let new_piece = Piece::new(piece_type, row, col);
+    /// Whether holding now would take the replacement piece from the next queue
+    /// True while the hold slot is empty; once a piece is held, holding swaps with it instead
+    pub fn hold_consumes_queue(&self) -> bool {
+        self.held_piece.is_none()
+    }
+    
+    /// Halt gravity, lock delay and player input for a "stop" power-up
+    /// Unlike pausing, the game stays Playing; moves, rotations, drops and holds fail until `thaw`
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+    
+    /// Let gravity and lock delay resume after `freeze`
+    pub fn thaw(&mut self) {
+        self.frozen = false;
+    }
+    
+    /// Whether the game is currently frozen
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+    
+    /// Spawns a new piece at the top of the board
+    fn spawn_new_piece(&mut self) {
+        let piece_type = match self.randomizer.next() {
+            Some(piece_type) => piece_type,
+            None => {
+                self.force_game_over(GameOverReason::OutOfPieces);
+                return;
+            }
+        };
+        let new_piece = spawn_piece(piece_type);
+        self.last_rotation_kick = None;
+        self.placement_inputs = 0;
         
         // Check for game over
         if !self.board.can_place(&new_piece) {
-            self.state = GameState::GameOver;
-            self.current_piece = None;
+            self.force_game_over(GameOverReason::BlockOut);
             return;
         }
         
         self.current_piece = Some(new_piece);
     }
     
+    /// Spawn the next piece once the entry delay is over, then apply the hold and
+    /// rotation buffered during the delay
+    /// A buffered rotation that doesn't fit at the spawn position is dropped
+    fn spawn_buffered_piece(&mut self) {
+        let rotation = self.buffered_rotation.take();
+        let hold = std::mem::take(&mut self.buffered_hold);
+        
+        self.spawn_new_piece();
+        if hold {
+            self.hold_piece();
+        }
+        
+        if let (Some(rotation), Some(piece)) = (rotation, self.current_piece.as_mut()) {
+            let previous = piece.rotation;
+            piece.rotation = rotation;
+            if !self.board.can_place(piece) {
+                piece.rotation = previous;
+            }
+        }
+    }
+    
+    /// Queue garbage lines sent by an opponent
+    /// They sit on the garbage meter for the configured delay, where attack can cancel them,
+    /// and are inserted at the first lock after the delay runs out
+    pub fn queue_garbage(&mut self, lines: usize) {
+        self.garbage_meter.queue(lines, self.config.garbage_delay);
+        self.stats.garbage_received += lines as u32;
+    }
+    
+    /// Total garbage lines received over the game
+    pub fn garbage_received(&self) -> u32 {
+        self.stats.garbage_received
+    }
+    
+    /// Total attack sent over the game
+    pub fn attack_sent(&self) -> u32 {
+        self.stats.attack_sent
+    }
+    
+    /// Garbage lines the most recent lock sent, counting any used to cancel incoming garbage
+    pub fn last_attack(&self) -> usize {
+        self.last_attack
+    }
+    
+    /// Whether the next Tetris or spin clear continues a back-to-back chain
+    pub fn is_back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+    
+    /// Line clears in a row minus one (0 after a single clear), or -1 when the last lock
+    /// cleared nothing
+    pub fn combo(&self) -> i32 {
+        self.combo
+    }
+    
+    /// Attack sent minus garbage received; negative when losing the exchange
+    pub fn net_attack(&self) -> i64 {
+        self.attack_sent() as i64 - self.garbage_received() as i64
+    }
+    
+    /// Number of garbage lines queued against this game, including those still in their delay
+    pub fn incoming_garbage(&self) -> usize {
+        self.garbage_meter.pending()
+    }
+    
+    /// Number of garbage lines past their delay, to be inserted at the next lock
+    pub fn ready_garbage(&self) -> usize {
+        self.garbage_meter.ready()
+    }
+    
+    /// Number of garbage lines on the meter, including those still in their delay
+    pub fn pending_garbage(&self) -> usize {
+        self.garbage_meter.pending()
+    }
+    
+    /// Time left in the pre-game countdown, if the game hasn't started yet
+    pub fn countdown_remaining(&self) -> Option<Duration> {
+        self.countdown_remaining
+    }
+    
+    /// A copy of the board showing only the cells of the last `reveal_recent` locked pieces
+    /// Used by "invisible" challenge modes where older pieces fade from view
+    pub fn visible_board_masked(&self, reveal_recent: usize) -> Board {
+        let mut masked = Board::new();
+        
+        for cells in self.recent_placements.iter().rev().take(reveal_recent) {
+            for &(row, col) in cells {
+                if let Some(&cell) = self.board.get_cell(row, col) {
+                    masked.set_cell(row, col, cell);
+                }
+            }
+        }
+        
+        masked
+    }
+    
+    /// Where the current piece would land if hard dropped
+    /// None if the piece is already resting there, since the ghost would only cover it
+    pub fn ghost_piece(&self) -> Option<Piece> {
+        self.ghost_and_drop_distance().map(|(ghost, _)| ghost)
+    }
+    
+    /// The ghost piece, but only once the current piece is at most `rows` rows above it
+    /// Harder difficulty settings hide the ghost until the piece is close to the stack
+    pub fn ghost_piece_if_within(&self, rows: usize) -> Option<Piece> {
+        self.ghost_and_drop_distance()
+            .filter(|&(_, distance)| distance <= rows)
+            .map(|(ghost, _)| ghost)
+    }
+    
+    /// How many rows the current piece would fall if hard dropped, without dropping it
+    /// None if there's no current piece
+    pub fn hard_drop_distance(&self) -> Option<u32> {
+        self.landing().map(|(_, distance)| distance as u32)
+    }
+    
+    /// The landing position of the current piece and how many rows it would drop to get there
+    /// None if there's no current piece or it has nowhere to drop
+    fn ghost_and_drop_distance(&self) -> Option<(Piece, usize)> {
+        self.landing().filter(|&(_, distance)| distance > 0)
+    }
+    
+    /// Where the current piece would land and how many rows it would drop to get there,
+    /// moving down until collision; None if there's no current piece
+    fn landing(&self) -> Option<(Piece, usize)> {
+        let mut piece = self.current_piece.clone()?;
+        let mut distance = 0;
+        while self.board.can_place(&piece.with_down_move()) {
+            piece = piece.with_down_move();
+            distance += 1;
+        }
+        Some((piece, distance))
+    }
+    
+    /// Whether the current piece has landed and its lock delay is running
+    pub fn is_locking(&self) -> bool {
+        self.lock_delay_active
+    }
+    
+    /// Whether the current piece is resting on the stack or floor and can't move down
+    /// False if there is no current piece
+    pub fn is_piece_grounded(&self) -> bool {
+        self.current_piece.as_ref()
+            .is_some_and(|piece| !self.board.can_place(&piece.with_down_move()))
+    }
+    
+    /// Time left before the current piece locks, while its lock delay is running
+    /// Renderers can use this to flash a piece that's about to lock
+    pub fn lock_delay_remaining(&self) -> Option<Duration> {
+        if self.lock_delay_active {
+            Some(self.lock_delay().saturating_sub(self.lock_delay_timer))
+        } else {
+            None
+        }
+    }
+    
+    /// Lock delay resets used by the current piece since it landed
+    pub fn lock_resets_used(&self) -> u8 {
+        self.lock_delay_resets
+    }
+    
+    /// Choose which pieces besides the T can score spins
+    pub fn set_spin_detection(&mut self, rule: SpinRule) {
+        self.config.spin_rule = rule;
+    }
+    
+    /// Height above the floor of the topmost block of the most recently locked piece, as it
+    /// landed before any lines cleared (1 for the bottom row); None before the first lock
+    pub fn last_landing_height(&self) -> Option<usize> {
+        self.last_landing_height
+    }
+    
+    /// The spin the most recently locked piece scored, `TSpinType::None` if it didn't spin
+    pub fn last_spin(&self) -> TSpinType {
+        self.last_spin
+    }
+    
+    /// Set the entry delay (ARE) between a piece locking and the next one spawning
+    pub fn set_are(&mut self, are: Duration) {
+        self.config.are = are;
+    }
+    
+    /// Whether the game is waiting out the entry delay, with no current piece
+    /// UIs can use this to hold off drawing the next piece
+    pub fn is_in_are(&self) -> bool {
+        self.are_remaining.is_some()
+    }
+    
+    /// Lock delay resets allowed before the piece is forced to lock
+    pub fn max_lock_resets(&self) -> u8 {
+        self.config.max_lock_resets
+    }
+    
     /// Get the upcoming pieces
     pub fn peek_next_pieces(&self, count: usize) -> Vec<PieceType> {
         self.randomizer.peek(count)
     }
+    
+    /// The upcoming pieces a player gets to see, `preview_length` of them
+    pub fn preview_pieces(&self) -> Vec<PieceType> {
+        self.randomizer.peek(self.config.preview_length)
+    }
+    
+    /// Human-readable dump of the whole engine state for bug reports
+    /// Not meant to be parsed; use replays or `encode_queue` for machine-readable state
+    pub fn debug_dump(&self) -> String {
+        let mut dump = String::new();
+        
+        // Writing to a String can't fail
+        let _ = writeln!(dump, "== State ==");
+        let _ = writeln!(dump, "State: {:?}", self.state);
+        let _ = writeln!(dump, "Score: {}", self.score_system.score);
+        let _ = writeln!(dump, "Level: {}", self.score_system.level);
+        let _ = writeln!(dump, "Lines: {}", self.score_system.lines_cleared);
+        if self.lock_delay_active {
+            let _ = writeln!(dump, "Lock delay: {:?} of {:?}, {}/{} resets used",
+                self.lock_delay_timer, self.config.lock_delay, self.lock_delay_resets, self.config.max_lock_resets);
+        } else {
+            let _ = writeln!(dump, "Lock delay: inactive");
+        }
+        
+        let _ = writeln!(dump, "== Pieces ==");
+        match self.current_piece {
+            Some(ref piece) => {
+                let _ = writeln!(dump, "Current: {:?} at row {}, col {}, facing {:?}",
+                    piece.piece_type, piece.row, piece.col, piece.rotation);
+            }
+            None => {
+                let _ = writeln!(dump, "Current: none");
+            }
+        }
+        match self.held_piece {
+            Some(piece_type) => {
+                let _ = writeln!(dump, "Held: {:?}{}", piece_type, if self.can_hold { "" } else { " (used)" });
+            }
+            None => {
+                let _ = writeln!(dump, "Held: none");
+            }
+        }
+        
+        let _ = writeln!(dump, "== Next ==");
+        let next: Vec<String> = self.randomizer.peek(ENCODED_QUEUE_LENGTH).iter()
+            .map(|piece_type| format!("{:?}", piece_type))
+            .collect();
+        let _ = writeln!(dump, "{}", next.join(" "));
+        
+        // The current piece is drawn in lowercase to tell it apart from the stack
+        let _ = writeln!(dump, "== Board ==");
+        let current = self.current_piece.as_ref()
+            .map(|piece| (piece.get_blocks(), format!("{:?}", piece.piece_type).to_lowercase()));
+        for row in 0..BOARD_HEIGHT {
+            let line: String = (0..BOARD_WIDTH)
+                .map(|col| match (&current, self.board.get_cell(row, col)) {
+                    (Some((cells, letter)), _) if cells.contains(&(row, col)) => letter.clone(),
+                    (_, Some(Cell::Filled(piece_type))) => format!("{:?}", piece_type),
+                    (_, Some(Cell::Garbage)) => "#".to_string(),
+                    _ => ".".to_string(),
+                })
+                .collect();
+            let _ = writeln!(dump, "{}", line);
+        }
+        
+        dump
+    }
+    
+    /// Pack the held piece and the next queue into one byte per piece for spectator syncing
+    /// The first byte is the held piece and the rest are the next pieces, with each piece
+    /// stored as its index plus one so that 0 can mean no held piece
+    pub fn encode_queue(&self) -> Vec<u8> {
+        let encode = |piece_type: PieceType| piece_type.to_index() as u8 + 1;
+        
+        let mut bytes = vec![self.held_piece.map_or(0, encode)];
+        bytes.extend(self.randomizer.peek(ENCODED_QUEUE_LENGTH).into_iter().map(encode));
+        bytes
+    }
+    
+    /// Unpack bytes from `encode_queue` into the held piece and a randomizer dealing the next queue
+    /// None if the bytes are empty or contain a value that isn't a piece
+    pub fn decode_queue(bytes: &[u8]) -> Option<(Option<PieceType>, ScriptedRandomizer)> {
+        let decode = |byte: u8| PieceType::from_index((byte as usize).checked_sub(1)?);
+        
+        let (&hold, next) = bytes.split_first()?;
+        let held_piece = match hold {
+            0 => None,
+            byte => Some(decode(byte)?),
+        };
+        let next_pieces = next.iter()
+            .map(|&byte| decode(byte))
+            .collect::<Option<Vec<PieceType>>>()?;
+        
+        Some((held_piece, ScriptedRandomizer::new(&next_pieces)))
+    }
 }
 
 // Implement Clone for the Game struct to allow the bot to simulate moves
@@ -580,14 +1555,1023 @@ impl Clone for Game {
                 score: self.score_system.score,
                 level: self.score_system.level,
                 lines_cleared: self.score_system.lines_cleared,
+                starting_level: self.score_system.starting_level,
+                score_before_level_up: self.score_system.score_before_level_up,
             },
+            stats: self.stats.clone(),
+            config: self.config,
             randomizer: self.randomizer.clone_box(),
+            starting_randomizer: self.starting_randomizer.as_ref().map(|randomizer| randomizer.clone_box()),
             time_since_last_drop: self.time_since_last_drop,
             gravity_delay: self.gravity_delay,
             lock_delay_timer: self.lock_delay_timer,
             lock_delay_active: self.lock_delay_active,
             lock_delay_resets: self.lock_delay_resets,
             last_successful_movement: self.last_successful_movement,
+            holds_used: self.holds_used,
+            last_rotation_kick: self.last_rotation_kick,
+            garbage_meter: self.garbage_meter.clone(),
+            garbage_rng: self.garbage_rng.clone(),
+            garbage_seed: self.garbage_seed,
+            back_to_back: self.back_to_back,
+            combo: self.combo,
+            recent_placements: self.recent_placements.clone(),
+            countdown_remaining: self.countdown_remaining,
+            placement_inputs: self.placement_inputs,
+            game_over_reason: self.game_over_reason,
+            frozen: self.frozen,
+            pieces_since_clear: self.pieces_since_clear,
+            are_remaining: self.are_remaining,
+            buffered_rotation: self.buffered_rotation,
+            buffered_hold: self.buffered_hold,
+            last_spin: self.last_spin,
+            last_landing_height: self.last_landing_height,
+            last_attack: self.last_attack,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_hold_limit() {
+        let mut game = Game::with_config(GameConfig {
+            total_hold_limit: Some(1),
+            ..GameConfig::default()
+        });
+        
+        // The first hold is allowed
+        assert!(game.hold_piece());
+        game.hard_drop();
+        
+        // The next piece could normally hold again, but the limit is used up
+        assert!(game.can_hold);
+        assert!(!game.hold_piece());
+    }
+    
+    #[test]
+    fn test_level_up_scoring_semantics() {
+        // Guideline: the single that reaches 10 lines is scored at level 1
+        let mut before = ScoreSystem::new();
+        before.lines_cleared = 9;
+        before.add_score_for_lines(1);
+        assert_eq!(before.level, 2);
+        assert_eq!(before.score, 100);
+        
+        // Alternative rules score it at the new level
+        let mut after = ScoreSystem::new();
+        after.score_before_level_up = false;
+        after.lines_cleared = 9;
+        after.add_score_for_lines_with_tspin(1, TSpinType::None);
+        assert_eq!(after.level, 2);
+        assert_eq!(after.score, 200);
+    }
+    
+    // Fills rows `rows` completely except for the given cells
+    fn fill_rows_except(game: &mut Game, rows: std::ops::Range<usize>, empty: &[(usize, usize)]) {
+        for row in rows {
+            for col in 0..BOARD_WIDTH {
+                if !empty.contains(&(row, col)) {
+                    game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_all_spin_mini_kicked_s_piece() {
+        let mut game = Game::with_config(GameConfig {
+            spin_rule: SpinRule::AllSpinMini,
+            ..GameConfig::default()
+        });
+        game.board.clear();
+        
+        // A flat S in a pocket, over a slot for a vertical S two rows further down
+        fill_rows_except(&mut game, 15..BOARD_HEIGHT, &[
+            (15, 3), (15, 4), (16, 4), (16, 5), (17, 3), (17, 4), (18, 3), (18, 4), (19, 3),
+        ]);
+        game.current_piece = Some(Piece::new(PieceType::S, 16, 4));
+        game.current_piece.as_mut().unwrap().rotation = Rotation::South;
+        
+        // The first three SRS tests for 2->L hit the stack, so the fourth kicks it down into the slot
+        assert_eq!(RotationSystem::kick_offsets(PieceType::S, Rotation::South, Rotation::West)[3], (2, 0));
+        assert!(game.rotate_clockwise());
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!((piece.row, piece.col, piece.rotation), (18, 4, Rotation::West));
+        assert!(!game.board.can_place(&piece.with_down_move()));
+        
+        // Kicked but still free to move up: mini
+        assert_eq!(game.detect_spin(), TSpinType::Mini);
+        
+        // A blocked move leaves the spin standing, but a successful one cancels it
+        assert!(!game.move_left());
+        assert_eq!(game.detect_spin(), TSpinType::Mini);
+        game.board.set_cell(18, 2, Cell::Empty);
+        game.board.set_cell(19, 2, Cell::Empty);
+        assert!(game.move_left());
+        assert_eq!(game.detect_spin(), TSpinType::None);
+    }
+    
+    #[test]
+    fn test_all_spin_mini_wedged_s_piece() {
+        let mut game = Game::with_config(GameConfig {
+            spin_rule: SpinRule::AllSpinMini,
+            ..GameConfig::default()
+        });
+        game.board.clear();
+        
+        // Leave room for the S piece before and after rotating in place
+        fill_rows_except(&mut game, 18..BOARD_HEIGHT, &[(20, 3), (20, 4), (21, 4), (21, 5), (20, 5), (19, 5)]);
+        game.current_piece = Some(Piece::new(PieceType::S, 20, 4));
+        
+        assert!(game.rotate_clockwise());
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!((piece.row, piece.col), (20, 4));
+        
+        // Unkicked but unable to move in any direction: full spin
+        assert_eq!(game.detect_spin(), TSpinType::Full);
+        
+        // Under the T-spin-only rule non-T pieces never spin
+        game.set_spin_detection(SpinRule::TSpinOnly);
+        assert_eq!(game.detect_spin(), TSpinType::None);
+    }
+    
+    #[test]
+    fn test_tspin_without_final_rotation() {
+        let mut game = Game::new();
+        
+        // A T with three filled corners still spins after moving, as T-spins only use the corner rule
+        for (row, col) in [(9, 3), (11, 3), (11, 5)] {
+            game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+        }
+        game.current_piece = Some(Piece::new(PieceType::T, 10, 4));
+        game.last_rotation_kick = None;
+        assert_eq!(game.detect_spin(), TSpinType::Full);
+    }
+    
+    #[test]
+    fn test_tspin_corner_rules() {
+        // For each rotation: one front corner and both back corners filled
+        let cases = [
+            (Rotation::North, [(1, -1), (-1, -1), (-1, 1)]),
+            (Rotation::East, [(1, 1), (-1, -1), (1, -1)]),
+            (Rotation::South, [(-1, 1), (1, -1), (1, 1)]),
+            (Rotation::West, [(-1, -1), (-1, 1), (1, 1)]),
+        ];
+        
+        for (rotation, filled) in cases {
+            let mut game = Game::new();
+            let (row, col) = (10, 4);
+            for (row_offset, col_offset) in filled {
+                game.board.set_cell((row + row_offset) as usize, (col + col_offset) as usize, Cell::Filled(PieceType::O));
+            }
+            let mut t_piece = Piece::new(PieceType::T, row, col);
+            t_piece.rotation = rotation;
+            game.current_piece = Some(t_piece);
+            
+            // The default guideline rule makes it a mini
+            assert_eq!(game.detect_tspin(), TSpinType::Mini, "strict {:?}", rotation);
+            game.config.strict_tspin_corners = false;
+            assert_eq!(game.detect_tspin(), TSpinType::Full, "lenient {:?}", rotation);
+        }
+    }
+    
+    #[test]
+    fn test_tspin_east_west_front_corners() {
+        // East and West used to check the corners behind the T, so a T pointing into both
+        // filled corners was only a mini under the strict rule
+        let cases = [
+            (Rotation::East, [(-1, 1), (1, 1), (-1, -1)]),
+            (Rotation::West, [(-1, -1), (1, -1), (1, 1)]),
+        ];
+        
+        for (rotation, filled) in cases {
+            let mut game = Game::new();
+            let (row, col) = (10, 4);
+            for (row_offset, col_offset) in filled {
+                game.board.set_cell((row + row_offset) as usize, (col + col_offset) as usize, Cell::Filled(PieceType::O));
+            }
+            let mut t_piece = Piece::new(PieceType::T, row, col);
+            t_piece.rotation = rotation;
+            game.current_piece = Some(t_piece);
+            
+            assert_eq!(game.detect_tspin(), TSpinType::Full, "{:?}", rotation);
+        }
+    }
+    
+    #[test]
+    fn test_tspin_mini_with_default_config() {
+        let mut game = Game::new();
+        let bottom = BOARD_HEIGHT as i32 - 1;
+        
+        // A T resting on its stem under two overhangs, with only one corner beside the stem filled
+        for (row, col) in [(bottom - 2, 3), (bottom - 2, 5), (bottom, 3)] {
+            game.board.set_cell(row as usize, col as usize, Cell::Filled(PieceType::O));
+        }
+        game.current_piece = Some(Piece::new(PieceType::T, bottom - 1, 4));
+        assert_eq!(game.detect_spin(), TSpinType::Mini);
+        
+        assert!(game.hard_drop());
+        assert_eq!(game.last_spin(), TSpinType::Mini);
+    }
+    
+    #[test]
+    fn test_are_delays_spawn() {
+        // Without an entry delay the next piece spawns as soon as one locks
+        let mut game = Game::new();
+        assert!(game.hard_drop());
+        assert!(!game.is_in_are());
+        assert!(game.current_piece.is_some());
+        
+        game.set_are(Duration::from_millis(100));
+        assert!(game.hard_drop());
+        assert!(game.is_in_are());
+        assert!(game.current_piece.is_none());
+        
+        game.update(Duration::from_millis(60));
+        assert!(game.is_in_are());
+        assert!(game.current_piece.is_none());
+        
+        game.update(Duration::from_millis(40));
+        assert!(!game.is_in_are());
+        assert!(game.current_piece.is_some());
+    }
+    
+    #[test]
+    fn test_initial_rotation_and_hold() {
+        let script = [PieceType::T, PieceType::L, PieceType::J, PieceType::S];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        game.set_are(Duration::from_millis(100));
+        
+        // A clockwise rotation during the delay spawns the L facing East
+        assert!(game.hard_drop());
+        assert!(game.rotate_clockwise());
+        game.update(Duration::from_millis(100));
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!((piece.piece_type, piece.rotation), (PieceType::L, Rotation::East));
+        
+        // A hold during the delay swaps the J out as soon as it appears
+        assert!(game.hard_drop());
+        assert!(game.hold_piece());
+        game.update(Duration::from_millis(100));
+        assert_eq!(game.held_piece, Some(PieceType::J));
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!((piece.piece_type, piece.rotation), (PieceType::S, Rotation::North));
+    }
+    
+    #[test]
+    fn test_is_piece_grounded() {
+        let mut game = Game::new();
+        assert!(!game.is_piece_grounded());
+        assert_eq!(game.lock_delay_remaining(), None);
+        
+        // On the floor the piece is grounded right away; the lock delay starts with gravity
+        game.current_piece = Some(Piece::new(PieceType::T, BOARD_HEIGHT as i32 - 2, 4));
+        assert!(game.is_piece_grounded());
+        game.update(Duration::from_millis(999));
+        assert_eq!(game.lock_delay_remaining(), None);
+        game.update(Duration::from_millis(1));
+        assert_eq!(game.lock_delay_remaining(), Some(LOCK_DELAY - Duration::from_millis(1)));
+        
+        game.update(Duration::from_millis(200));
+        assert_eq!(game.lock_delay_remaining(), Some(LOCK_DELAY - Duration::from_millis(201)));
+    }
+    
+    #[test]
+    fn test_all_immobile_s_spin() {
+        let mut game = Game::new();
+        game.set_spin_detection(SpinRule::AllImmobile);
+        
+        // The same wedge as the all-spin-mini test: the S rotates in place and can't move
+        fill_rows_except(&mut game, 18..BOARD_HEIGHT, &[(20, 3), (20, 4), (21, 4), (21, 5), (20, 5), (19, 5)]);
+        game.current_piece = Some(Piece::new(PieceType::S, 20, 4));
+        assert!(game.rotate_clockwise());
+        assert_eq!(game.detect_spin(), TSpinType::Full);
+        
+        // Locking it scores the spin and reports it
+        assert!(game.hard_drop());
+        assert_eq!(game.last_spin(), TSpinType::Full);
+        assert!(game.score_system.score >= 400);
+        
+        // Under the T-spin-only rule the same placement isn't a spin
+        game.set_spin_detection(SpinRule::TSpinOnly);
+        game.board.clear();
+        fill_rows_except(&mut game, 18..BOARD_HEIGHT, &[(20, 3), (20, 4), (21, 4), (21, 5), (20, 5), (19, 5)]);
+        game.current_piece = Some(Piece::new(PieceType::S, 20, 4));
+        assert!(game.rotate_clockwise());
+        assert_eq!(game.detect_spin(), TSpinType::None);
+    }
+    
+    #[test]
+    fn test_last_landing_height() {
+        let mut game = Game::new();
+        assert_eq!(game.last_landing_height(), None);
+        
+        // Even when the lock clears a line, the height is where the piece landed
+        fill_rows_except(&mut game, BOARD_HEIGHT - 1..BOARD_HEIGHT, &[(BOARD_HEIGHT - 1, 0)]);
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 0, Rotation::West));
+        assert_eq!(game.stats.lines_cleared(), 1);
+        assert_eq!(game.last_landing_height(), Some(4));
+    }
+    
+    #[test]
+    fn test_hard_drop_distance() {
+        let mut game = Game::new();
+        game.current_piece = Some(Piece::new(PieceType::O, 0, 4));
+        assert_eq!(game.hard_drop_distance(), Some(BOARD_HEIGHT as u32 - 2));
+        
+        // The preview matches the points a real hard drop awards
+        let score = game.score_system.score;
+        assert!(game.hard_drop());
+        assert_eq!(game.score_system.score - score, 2 * (BOARD_HEIGHT as u32 - 2));
+        
+        game.current_piece = None;
+        assert_eq!(game.hard_drop_distance(), None);
+    }
+    
+    #[test]
+    fn test_is_locking() {
+        let mut game = Game::new();
+        assert!(!game.is_locking());
+        
+        // Put a piece on the floor and let gravity notice it has landed
+        game.current_piece = Some(Piece::new(PieceType::T, BOARD_HEIGHT as i32 - 2, 4));
+        game.update(Duration::from_millis(999));
+        assert!(!game.is_locking());
+        game.update(Duration::from_millis(1));
+        assert!(game.is_locking());
+        
+        // Once the delay expires the piece locks and the next one is free again
+        game.update(LOCK_DELAY);
+        assert!(!game.is_locking());
+    }
+    
+    #[test]
+    fn test_incoming_garbage_cancelled_by_attack() {
+        let mut game = Game::new();
+        game.board.clear();
+        
+        // Two rows missing only the two leftmost cells, so an O piece clears a double
+        fill_rows_except(&mut game, BOARD_HEIGHT - 2..BOARD_HEIGHT, &[
+            (BOARD_HEIGHT - 2, 0), (BOARD_HEIGHT - 2, 1), (BOARD_HEIGHT - 1, 0), (BOARD_HEIGHT - 1, 1),
+        ]);
+        // A stray cell above keeps the clear from being a perfect clear
+        game.board.set_cell(BOARD_HEIGHT - 3, 9, Cell::Filled(PieceType::O));
+        game.current_piece = Some(Piece::new(PieceType::O, 0, 0));
+        
+        game.queue_garbage(4);
+        assert_eq!(game.incoming_garbage(), 4);
+        game.hard_drop();
+        
+        // The double sends one line, so three of the four are inserted
+        let garbage_rows = (0..BOARD_HEIGHT)
+            .filter(|&row| (0..BOARD_WIDTH).any(|col| game.board.get_cell(row, col) == Some(&Cell::Garbage)))
+            .count();
+        assert_eq!(garbage_rows, 3);
+        assert_eq!(game.incoming_garbage(), 0);
+        assert_eq!(game.score_system.lines_cleared, 2);
+    }
+    
+    #[test]
+    fn test_garbage_meter_delay() {
+        let mut game = Game::with_config(GameConfig {
+            garbage_delay: Duration::from_secs(1),
+            ..GameConfig::default()
+        });
+        let garbage_rows = |game: &Game| (0..BOARD_HEIGHT)
+            .filter(|&row| (0..BOARD_WIDTH).any(|col| game.board.get_cell(row, col) == Some(&Cell::Garbage)))
+            .count();
+        
+        game.queue_garbage(3);
+        assert_eq!(game.pending_garbage(), 3);
+        assert_eq!(game.incoming_garbage(), 3);
+        assert_eq!(game.ready_garbage(), 0);
+        
+        // Locking during the delay leaves the garbage on the meter
+        game.update(Duration::from_millis(500));
+        game.hard_drop();
+        assert_eq!(garbage_rows(&game), 0);
+        assert_eq!(game.pending_garbage(), 3);
+        
+        // Once the delay has passed it goes in at the next lock
+        game.update(Duration::from_millis(500));
+        assert_eq!(game.ready_garbage(), 3);
+        game.hard_drop();
+        assert_eq!(garbage_rows(&game), 3);
+        assert_eq!(game.pending_garbage(), 0);
+    }
+    
+    #[test]
+    fn test_freeze_halts_gravity() {
+        let mut game = Game::new();
+        let start_row = game.current_piece.as_ref().unwrap().row;
+        
+        game.freeze();
+        assert!(game.update(Duration::from_secs(5)));
+        assert_eq!(game.state, GameState::Playing);
+        assert_eq!(game.current_piece.as_ref().unwrap().row, start_row);
+        
+        // Input is ignored while frozen
+        assert!(!game.move_left());
+        assert!(!game.move_right());
+        assert!(!game.rotate_clockwise());
+        assert!(!game.rotate_counterclockwise());
+        assert!(!game.hold_piece());
+        assert!(!game.hard_drop());
+        assert_eq!(game.current_piece.as_ref().unwrap().row, start_row);
+        assert_eq!(game.stats.pieces_placed, 0);
+        
+        // Gravity and input pick up again once thawed
+        game.thaw();
+        assert!(game.move_left());
+        assert!(game.move_right());
+        game.update(Duration::from_secs(1));
+        assert_eq!(game.current_piece.as_ref().unwrap().row, start_row + 1);
+    }
+    
+    #[test]
+    fn test_start_with_cheese() {
+        let game = Game::start_with_cheese(6, 42);
+        assert_eq!(game.state, GameState::Playing);
+        
+        let gaps: Vec<usize> = (BOARD_HEIGHT - 6..BOARD_HEIGHT)
+            .map(|row| {
+                let empty: Vec<usize> = (0..BOARD_WIDTH)
+                    .filter(|&col| game.board.get_cell(row, col) == Some(&Cell::Empty))
+                    .collect();
+                // Exactly one gap, so no row is complete
+                assert_eq!(empty.len(), 1);
+                empty[0]
+            })
+            .collect();
+        assert!(gaps.windows(2).all(|pair| pair[0] != pair[1]));
+        
+        // Nothing above the cheese
+        assert!((0..BOARD_WIDTH).all(|col| game.board.get_cell(BOARD_HEIGHT - 7, col) == Some(&Cell::Empty)));
+        
+        // The same seed produces the same cheese
+        assert_eq!(Game::start_with_cheese(6, 42).board, game.board);
+    }
+    
+    #[test]
+    fn test_countdown_before_play() {
+        let mut game = Game::with_config(GameConfig {
+            countdown: Some(Duration::from_secs(3)),
+            ..GameConfig::default()
+        });
+        assert_eq!(game.state, GameState::Ready);
+        assert!(game.current_piece.is_none());
+        
+        // Inputs do nothing during the countdown
+        game.update(Duration::from_secs(2));
+        assert!(!game.hard_drop());
+        assert!(!game.hold_piece());
+        assert_eq!(game.state, GameState::Ready);
+        assert_eq!(game.countdown_remaining(), Some(Duration::from_secs(1)));
+        assert!(game.current_piece.is_none());
+        
+        game.update(Duration::from_secs(1));
+        assert_eq!(game.state, GameState::Playing);
+        assert!(game.current_piece.is_some());
+        assert_eq!(game.countdown_remaining(), None);
+    }
+    
+    #[test]
+    fn test_new_empty_waits_for_start() {
+        let mut game = Game::new_empty();
+        assert_eq!(game.state, GameState::Ready);
+        assert!(game.current_piece.is_none());
+        
+        // Time passing doesn't start the game
+        assert!(!game.update(Duration::from_secs(5)));
+        assert!(game.current_piece.is_none());
+        
+        assert!(game.start());
+        assert_eq!(game.state, GameState::Playing);
+        assert!(game.current_piece.is_some());
+        assert!(!game.start());
+    }
+    
+    #[test]
+    fn test_visible_board_masked() {
+        let mut game = Game::new();
+        
+        // Drop three O pieces side by side
+        for col in [0, 2, 4] {
+            game.current_piece = Some(Piece::new(PieceType::O, 0, col));
+            game.hard_drop();
+        }
+        
+        let masked = game.visible_board_masked(1);
+        let filled: Vec<(usize, usize)> = (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .filter(|&(row, col)| masked.get_cell(row, col).is_some_and(|cell| cell.is_filled()))
+            .collect();
+        assert_eq!(filled, vec![
+            (BOARD_HEIGHT - 2, 4), (BOARD_HEIGHT - 2, 5), (BOARD_HEIGHT - 1, 4), (BOARD_HEIGHT - 1, 5),
+        ]);
+        
+        // Revealing everything matches the real board
+        assert_eq!(game.visible_board_masked(3), game.board);
+    }
+    
+    #[test]
+    fn test_finesse_faults() {
+        let mut game = Game::new();
+        
+        // One left is enough to reach the spot
+        game.move_left();
+        game.hard_drop();
+        assert_eq!(game.stats.finesse_faults, 0);
+        
+        // Left, left, right reaches the same column the long way round
+        game.move_left();
+        game.move_left();
+        game.move_right();
+        game.hard_drop();
+        assert_eq!(game.stats.finesse_faults, 1);
+        assert_eq!(game.stats.pieces_placed, 2);
+    }
+    
+    #[test]
+    fn test_force_game_over() {
+        let mut game = Game::new();
+        assert_eq!(game.game_over_reason(), None);
+        
+        game.force_game_over(GameOverReason::TimeLimit);
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.game_over_reason(), Some(GameOverReason::TimeLimit));
+        assert!(game.current_piece.is_none());
+        
+        // Inputs no longer do anything
+        let board = game.board.clone();
+        assert!(!game.move_left());
+        assert!(!game.rotate_clockwise());
+        assert!(!game.hard_drop());
+        assert!(!game.hold_piece());
+        assert!(!game.update(Duration::from_secs(1)));
+        assert_eq!(game.board, board);
+        
+        game.reset();
+        assert_eq!(game.game_over_reason(), None);
+    }
+    
+    #[test]
+    fn test_randomizer_running_out_ends_game() {
+        let randomizer = ScriptedRandomizer::new(&[PieceType::O, PieceType::I]);
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(randomizer));
+        
+        game.hard_drop();
+        assert_eq!(game.state, GameState::Playing);
+        
+        // The last piece can't be held away, and dropping it ends the game cleanly
+        assert!(!game.hold_piece());
+        game.hard_drop();
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.game_over_reason(), Some(GameOverReason::OutOfPieces));
+    }
+    
+    #[test]
+    fn test_lock_resets_used() {
+        let mut game = Game::with_config(GameConfig {
+            max_lock_resets: 3,
+            ..Default::default()
+        });
+        assert_eq!(game.max_lock_resets(), 3);
+        
+        // Land a T piece to start the lock delay
+        game.current_piece = Some(Piece::new(PieceType::T, BOARD_HEIGHT as i32 - 2, 4));
+        assert!(!game.move_down());
+        assert_eq!(game.lock_resets_used(), 0);
+        
+        // Each rotation resets the delay until the limit is reached
+        for expected in [1, 2, 3, 3, 3] {
+            assert!(game.rotate_clockwise());
+            assert_eq!(game.lock_resets_used(), expected);
+        }
+    }
+    
+    #[test]
+    fn test_new_match() {
+        let piece_order = |game: &Game| {
+            let mut order = vec![game.current_piece.as_ref().unwrap().piece_type];
+            order.extend(game.peek_next_pieces(5));
+            order
+        };
+        
+        let first = Game::new_match(42);
+        let second = Game::new_match(42);
+        assert_eq!(piece_order(&first), piece_order(&second));
+        
+        let other = Game::new_match(43);
+        assert_ne!(piece_order(&first), piece_order(&other));
+    }
+    
+    #[test]
+    fn test_new_match_garbage_gaps() {
+        // Both players receive the same garbage, one line per piece
+        let receive_garbage = |game: &mut Game| {
+            for _ in 0..5 {
+                game.queue_garbage(1);
+                game.hard_drop();
+            }
+        };
+        
+        let mut first = Game::new_match(42);
+        let mut second = Game::new_match(42);
+        receive_garbage(&mut first);
+        receive_garbage(&mut second);
+        assert_eq!(first.board, second.board);
+        
+        // Resetting replays the same gaps
+        first.reset();
+        receive_garbage(&mut first);
+        assert_eq!(first.board, second.board);
+    }
+    
+    #[test]
+    fn test_net_attack() {
+        let mut game = Game::new();
+        
+        // Four rows missing the leftmost cell, waiting on a vertical I piece
+        let well: Vec<(usize, usize)> = (BOARD_HEIGHT - 4..BOARD_HEIGHT).map(|row| (row, 0)).collect();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 4..BOARD_HEIGHT, &well);
+        game.board.set_cell(BOARD_HEIGHT - 5, 9, Cell::Filled(PieceType::O));
+        let mut i_piece = Piece::new(PieceType::I, 1, 0);
+        i_piece.rotate_counterclockwise();
+        game.current_piece = Some(i_piece);
+        
+        game.queue_garbage(6);
+        game.hard_drop();
+        
+        assert_eq!(game.score_system.lines_cleared, 4);
+        assert_eq!(game.garbage_received(), 6);
+        assert_eq!(game.attack_sent(), 4);
+        assert_eq!(game.net_attack(), -2);
+    }
+    
+    #[test]
+    fn test_attack_lines() {
+        assert_eq!(attack_lines(0, TSpinType::Full, true, 5, false), 0);
+        assert_eq!(attack_lines(1, TSpinType::None, false, -1, false), 0);
+        assert_eq!(attack_lines(4, TSpinType::None, false, -1, false), 4);
+        assert_eq!(attack_lines(2, TSpinType::Full, false, -1, false), 4);
+        
+        // Back-to-back adds one, combos ramp up, and a perfect clear adds ten
+        assert_eq!(attack_lines(4, TSpinType::None, true, -1, false), 5);
+        assert_eq!(attack_lines(1, TSpinType::None, false, 2, false), 1);
+        assert_eq!(attack_lines(1, TSpinType::None, false, 20, false), 5);
+        assert_eq!(attack_lines(4, TSpinType::None, true, 0, true), 15);
+    }
+    
+    #[test]
+    fn test_attack_counters_incoming_garbage() {
+        let mut game = Game::new();
+        game.queue_garbage(3);
+        
+        // A perfect clear Tetris sends fourteen lines, three of which cancel the incoming garbage
+        let well: Vec<(usize, usize)> = (BOARD_HEIGHT - 4..BOARD_HEIGHT).map(|row| (row, 0)).collect();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 4..BOARD_HEIGHT, &well);
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 0, Rotation::West));
+        assert_eq!(game.last_attack(), 14);
+        assert_eq!(game.pending_garbage(), 0);
+        assert!(game.board.is_perfect_clear());
+        
+        // A lock that sends nothing lets the next batch through
+        game.queue_garbage(2);
+        assert!(game.place_piece_at(PieceType::O, 10, 4, Rotation::North));
+        assert_eq!(game.last_attack(), 0);
+        assert_eq!(game.pending_garbage(), 0);
+        assert_eq!(game.board.filled_count_in_row(BOARD_HEIGHT - 1), BOARD_WIDTH - 1);
+        assert_eq!(game.board.filled_count_in_row(BOARD_HEIGHT - 2), BOARD_WIDTH - 1);
+    }
+    
+    #[test]
+    fn test_gravity_disabled() {
+        let mut game = Game::with_config(GameConfig {
+            gravity_enabled: false,
+            ..Default::default()
+        });
+        let row = game.current_piece.as_ref().unwrap().row;
+        
+        for _ in 0..30 {
+            game.update(Duration::from_secs(1));
+        }
+        assert_eq!(game.current_piece.as_ref().unwrap().row, row);
+        
+        // Manual drops still work
+        assert!(game.move_down());
+        assert_eq!(game.current_piece.as_ref().unwrap().row, row + 1);
+        assert!(game.hard_drop());
+        assert_eq!(game.stats.pieces_placed, 1);
+    }
+    
+    #[test]
+    fn test_encode_queue_round_trip() {
+        let script = [PieceType::T, PieceType::I, PieceType::O, PieceType::S, PieceType::Z, PieceType::J, PieceType::L];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        
+        // Holding the T leaves the I in play and five pieces in the queue
+        assert!(game.hold_piece());
+        let bytes = game.encode_queue();
+        assert_eq!(bytes, vec![3, 2, 4, 5, 6, 7]);
+        
+        let (held_piece, randomizer) = Game::decode_queue(&bytes).unwrap();
+        assert_eq!(held_piece, Some(PieceType::T));
+        assert_eq!(randomizer.peek(ENCODED_QUEUE_LENGTH), script[2..].to_vec());
+        
+        // No hold is encoded as 0, and bytes past the piece range are rejected
+        assert_eq!(Game::decode_queue(&[0, 3]).unwrap().0, None);
+        assert!(Game::decode_queue(&[0, 8]).is_none());
+        assert!(Game::decode_queue(&[]).is_none());
+    }
+    
+    #[test]
+    fn test_hard_drop_scored_without_points() {
+        let mut game = Game::new();
+        
+        // Two rows missing only the two leftmost cells, so an O piece clears a double
+        fill_rows_except(&mut game, BOARD_HEIGHT - 2..BOARD_HEIGHT, &[
+            (BOARD_HEIGHT - 2, 0), (BOARD_HEIGHT - 2, 1), (BOARD_HEIGHT - 1, 0), (BOARD_HEIGHT - 1, 1),
+        ]);
+        game.board.set_cell(BOARD_HEIGHT - 3, BOARD_WIDTH - 1, Cell::Filled(PieceType::O)); // No perfect clear
+        game.current_piece = Some(Piece::new(PieceType::O, 0, 0));
+        
+        // Only the double counts, not the rows dropped
+        assert!(game.hard_drop_scored(false));
+        assert_eq!(game.stats.pieces_placed, 1);
+        assert_eq!(game.score_system.lines_cleared, 2);
+        assert_eq!(game.score_system.score, 300);
+    }
+    
+    #[test]
+    fn test_first_hold_consumes_queue() {
+        let mut game = Game::new();
+        let current = game.current_piece.as_ref().unwrap().piece_type;
+        let next = game.peek_next_pieces(2);
+        assert!(game.hold_consumes_queue());
+        
+        // The front of the queue becomes the active piece and the queue moves up
+        assert!(game.hold_piece());
+        assert_eq!(game.current_piece.as_ref().unwrap().piece_type, next[0]);
+        assert_eq!(game.peek_next_pieces(1)[0], next[1]);
+        assert_eq!(game.held_piece, Some(current));
+        
+        // Later holds swap with the held piece instead
+        assert!(!game.hold_consumes_queue());
+    }
+    
+    #[test]
+    fn test_piece_gravity_multipliers() {
+        let mut piece_gravity_multipliers = [1.0; 7];
+        piece_gravity_multipliers[PieceType::I.to_index()] = 2.0;
+        let config = GameConfig {
+            piece_gravity_multipliers,
+            ..GameConfig::default()
+        };
+        
+        let rows_fallen = |piece_type: PieceType| {
+            let mut game = Game::with_config(config);
+            game.current_piece = Some(Piece::new(piece_type, 2, 4));
+            for _ in 0..4 {
+                game.update(Duration::from_millis(500));
+            }
+            game.current_piece.as_ref().unwrap().row - 2
+        };
+        
+        assert_eq!(rows_fallen(PieceType::T), 2);
+        assert_eq!(rows_fallen(PieceType::I), 4);
+    }
+    
+    #[test]
+    fn test_invalid_piece_gravity_multipliers() {
+        let mut piece_gravity_multipliers = [1.0; 7];
+        piece_gravity_multipliers[PieceType::T.to_index()] = 0.0;
+        piece_gravity_multipliers[PieceType::I.to_index()] = -2.0;
+        piece_gravity_multipliers[PieceType::O.to_index()] = f64::NAN;
+        piece_gravity_multipliers[PieceType::S.to_index()] = f64::MIN_POSITIVE;
+        let config = GameConfig {
+            piece_gravity_multipliers,
+            ..GameConfig::default()
+        };
+        
+        let rows_fallen = |piece_type: PieceType| {
+            let mut game = Game::with_config(config);
+            game.current_piece = Some(Piece::new(piece_type, 2, 4));
+            for _ in 0..4 {
+                game.update(Duration::from_millis(500));
+            }
+            game.current_piece.as_ref().unwrap().row - 2
+        };
+        
+        // Invalid multipliers fall at the level's rate instead of panicking
+        assert_eq!(rows_fallen(PieceType::T), 2);
+        assert_eq!(rows_fallen(PieceType::I), 2);
+        assert_eq!(rows_fallen(PieceType::O), 2);
+        // A vanishingly small one barely moves the piece at all
+        assert_eq!(rows_fallen(PieceType::S), 0);
+    }
+    
+    #[test]
+    fn test_ghost_piece_if_within() {
+        let mut game = Game::new();
+        game.current_piece = Some(Piece::new(PieceType::T, 2, 4));
+        
+        // Far above the empty floor the ghost stays hidden
+        assert!(game.ghost_piece().is_some());
+        assert!(game.ghost_piece_if_within(3).is_none());
+        
+        while game.ghost_piece_if_within(3).is_none() {
+            assert!(game.move_down());
+        }
+        let ghost = game.ghost_piece_if_within(3).unwrap();
+        assert_eq!(ghost.get_blocks(), game.ghost_piece().unwrap().get_blocks());
+        assert_eq!(ghost.row - game.current_piece.as_ref().unwrap().row, 3);
+    }
+    
+    #[test]
+    fn test_debug_dump() {
+        let script = [PieceType::T, PieceType::I, PieceType::O];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        game.score_system.score = 1234;
+        
+        let dump = game.debug_dump();
+        assert!(dump.contains("Score: 1234"));
+        assert!(dump.contains("Current: T"));
+        assert!(dump.contains("== Next ==\nI O\n"));
+        assert!(dump.contains("== Board =="));
+    }
+    
+    #[test]
+    fn test_reset_lock_on_failed_input() {
+        for reset_lock_on_failed_input in [false, true] {
+            let mut game = Game::with_config(GameConfig {
+                reset_lock_on_failed_input,
+                ..GameConfig::default()
+            });
+            
+            // An O piece resting on the floor against the left wall
+            game.current_piece = Some(Piece::new(PieceType::O, BOARD_HEIGHT as i32 - 2, 0));
+            assert!(!game.move_down());
+            game.update(Duration::from_millis(300));
+            assert!(game.is_locking());
+            
+            // Pressing into the wall only restarts the timer under the classic rule
+            assert!(!game.move_left());
+            let expected = if reset_lock_on_failed_input { Duration::ZERO } else { Duration::from_millis(300) };
+            assert_eq!(game.lock_delay_timer, expected);
+        }
+    }
+    
+    #[test]
+    fn test_retry_replays_sequence() {
+        let first_pieces = |game: &Game| {
+            let mut pieces = vec![game.current_piece.as_ref().unwrap().piece_type];
+            pieces.extend(game.peek_next_pieces(5));
+            pieces
+        };
+        
+        for mut game in [Game::new_match(42), Game::new()] {
+            let original = first_pieces(&game);
+            for _ in 0..3 {
+                game.hard_drop();
+            }
+            
+            game.retry();
+            assert!(game.board.is_perfect_clear());
+            assert_eq!(game.score_system.score, 0);
+            assert_eq!(first_pieces(&game), original);
+        }
+    }
+    
+    #[test]
+    fn test_can_spawn_next() {
+        let script = [PieceType::T, PieceType::S];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        assert!(game.can_spawn_next());
+        
+        // Fill the hidden rows over the columns the next piece spawns into
+        for row in 0..2 {
+            for col in 3..7 {
+                game.board.set_cell(row, col, Cell::Garbage);
+            }
         }
+        assert!(!game.can_spawn_next());
+        
+        // A game that has run out of pieces has nothing to spawn
+        let game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&[PieceType::T])));
+        assert!(!game.can_spawn_next());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_no_ghost_for_resting_piece() {
+        let mut game = Game::new();
+        game.current_piece = Some(Piece::new(PieceType::O, BOARD_HEIGHT as i32 - 2, 4));
+        
+        assert!(game.ghost_piece().is_none());
+        assert!(game.ghost_piece_if_within(3).is_none());
+    }
+    
+    #[test]
+    fn test_back_to_back_and_combo() {
+        let mut game = Game::new();
+        game.board.set_cell(BOARD_HEIGHT - 9, 0, Cell::Filled(PieceType::O));
+        
+        // Two Tetrises in a row: the second is back-to-back and a one-clear combo
+        let well: Vec<(usize, usize)> = (BOARD_HEIGHT - 8..BOARD_HEIGHT).map(|row| (row, 9)).collect();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 8..BOARD_HEIGHT, &well);
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 9, Rotation::West));
+        assert_eq!(game.stats.attack_sent, 4);
+        assert!(game.is_back_to_back());
+        assert_eq!(game.combo(), 0);
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 9, Rotation::West));
+        assert_eq!(game.last_attack(), 5);
+        assert_eq!(game.combo(), 1);
+        
+        // A lock without a clear ends the combo but not the back-to-back chain
+        assert!(game.place_piece_at(PieceType::O, 10, 4, Rotation::North));
+        assert_eq!(game.combo(), -1);
+        assert!(game.is_back_to_back());
+    }
+    
+    #[test]
+    fn test_set_current_piece() {
+        let mut game = Game::new();
+        let next = game.peek_next_pieces(3);
+        
+        assert!(game.set_current_piece(PieceType::I));
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!((piece.piece_type, piece.rotation), (PieceType::I, Rotation::North));
+        assert_eq!(game.peek_next_pieces(3), next);
+        
+        // A piece that can't spawn is refused and the I stays
+        fill_rows_except(&mut game, 0..2, &[]);
+        assert!(!game.set_current_piece(PieceType::T));
+        assert_eq!(game.current_piece.as_ref().unwrap().piece_type, PieceType::I);
+    }
+    
+    #[test]
+    fn test_place_piece_at() {
+        let mut game = Game::new();
+        let current = game.current_piece.as_ref().map(|piece| (piece.piece_type, piece.row, piece.col));
+        
+        // A flat I covers the pivot column, one to its left and two to its right
+        assert!(game.place_piece_at(PieceType::I, 15, 4, Rotation::North));
+        for col in 0..BOARD_WIDTH {
+            let expected = if (3..=6).contains(&col) { Cell::Filled(PieceType::I) } else { Cell::Empty };
+            assert_eq!(game.board.get_cell(15, col), Some(&expected));
+        }
+        assert_eq!(game.current_piece.as_ref().map(|piece| (piece.piece_type, piece.row, piece.col)), current);
+        
+        assert_eq!(game.stats.pieces_placed, 1);
+        
+        // Overlapping the placed piece or the current piece, or hanging off the board, is rejected
+        assert!(!game.place_piece_at(PieceType::O, 14, 5, Rotation::North));
+        assert!(!game.place_piece_at(PieceType::I, 15, 8, Rotation::North));
+        let (spawn_row, spawn_col) = game.current_piece.as_ref().map(|piece| (piece.row, piece.col)).unwrap();
+        assert!(!game.place_piece_at(PieceType::O, spawn_row as usize, spawn_col as usize, Rotation::North));
+        assert_eq!(game.stats.pieces_placed, 1);
+        
+        // Completing a line clears and scores it
+        let bottom = BOARD_HEIGHT - 1;
+        fill_rows_except(&mut game, bottom..BOARD_HEIGHT, &[(bottom, 3), (bottom, 4), (bottom, 5), (bottom, 6)]);
+        assert!(game.place_piece_at(PieceType::I, bottom, 4, Rotation::North));
+        assert_eq!(game.stats.lines_cleared(), 1);
+        assert!(game.score_system.score > 0);
+    }
+    
+    #[test]
+    fn test_garbage_row_clears_once_gap_filled() {
+        let mut game = Game::new();
+        game.board.insert_garbage_rows(1, 0);
+        
+        // Locking a piece elsewhere leaves the garbage row in place
+        assert!(game.place_piece_at(PieceType::O, BOARD_HEIGHT - 3, 4, Rotation::North));
+        assert_eq!(game.stats.lines_cleared(), 0);
+        assert_eq!(game.board.get_cell(BOARD_HEIGHT - 1, 1), Some(&Cell::Garbage));
+        
+        // A vertical I plugging the gap completes and clears the row
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 0, Rotation::West));
+        assert_eq!(game.stats.lines_cleared(), 1);
+        assert!(game.score_system.score > 0);
+        assert!((0..BOARD_WIDTH).all(|col| game.board.get_cell(BOARD_HEIGHT - 1, col) != Some(&Cell::Garbage)));
+    }
+    
+    #[test]
+    fn test_pieces_since_clear() {
+        let mut game = Game::new();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 1..BOARD_HEIGHT, &[(BOARD_HEIGHT - 1, 0)]);
+        
+        // Three pieces stacked on the right leave the gap open
+        assert!(game.place_piece_at(PieceType::O, BOARD_HEIGHT - 3, 8, Rotation::North));
+        assert!(game.place_piece_at(PieceType::O, BOARD_HEIGHT - 5, 8, Rotation::North));
+        assert!(game.place_piece_at(PieceType::O, BOARD_HEIGHT - 7, 8, Rotation::North));
+        assert_eq!(game.pieces_since_clear(), 3);
+        
+        // Plugging the gap clears the bottom row and ends the drought
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 0, Rotation::West));
+        assert_eq!(game.pieces_since_clear(), 0);
+    }
+}
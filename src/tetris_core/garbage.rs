@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Garbage lines sent by opponents that haven't reached the board yet
+/// Each batch waits out a delay before it can be inserted, and attack cancels
+/// the oldest batches first whether or not their delay has run out
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GarbageMeter {
+    // Pending batches, oldest first, as (lines, time left before they can be inserted)
+    batches: VecDeque<(usize, Duration)>,
+}
+
+impl GarbageMeter {
+    /// Create an empty meter
+    pub fn new() -> Self {
+        GarbageMeter {
+            batches: VecDeque::new(),
+        }
+    }
+
+    /// Add a batch of `lines` that becomes insertable after `delay`
+    pub fn queue(&mut self, lines: usize, delay: Duration) {
+        if lines > 0 {
+            self.batches.push_back((lines, delay));
+        }
+    }
+
+    /// Count down the delay of every pending batch
+    pub fn tick(&mut self, dt: Duration) {
+        for (_, remaining) in self.batches.iter_mut() {
+            *remaining = remaining.saturating_sub(dt);
+        }
+    }
+
+    /// Offset pending lines with `attack`, oldest batches first
+    /// Returns the attack left over once the meter is empty
+    pub fn cancel(&mut self, mut attack: usize) -> usize {
+        while attack > 0 {
+            let Some((lines, _)) = self.batches.front_mut() else {
+                break;
+            };
+            let cancelled = attack.min(*lines);
+            *lines -= cancelled;
+            attack -= cancelled;
+            if *lines == 0 {
+                self.batches.pop_front();
+            }
+        }
+        attack
+    }
+
+    /// Remove the batches whose delay has run out and return their total lines
+    pub fn take_ready(&mut self) -> usize {
+        let ready = self.ready();
+        self.batches.retain(|&(_, remaining)| !remaining.is_zero());
+        ready
+    }
+
+    /// Lines whose delay has run out, to be inserted at the next lock
+    pub fn ready(&self) -> usize {
+        self.batches.iter()
+            .filter(|(_, remaining)| remaining.is_zero())
+            .map(|&(lines, _)| lines)
+            .sum()
+    }
+
+    /// All lines on the meter, including those still in their delay
+    pub fn pending(&self) -> usize {
+        self.batches.iter().map(|&(lines, _)| lines).sum()
+    }
+
+    /// Drop every pending batch
+    pub fn clear(&mut self) {
+        self.batches.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_oldest_first() {
+        let mut meter = GarbageMeter::new();
+        meter.queue(2, Duration::ZERO);
+        meter.queue(3, Duration::from_secs(1));
+        assert_eq!(meter.pending(), 5);
+        assert_eq!(meter.ready(), 2);
+
+        // The ready batch goes first, then part of the delayed one
+        assert_eq!(meter.cancel(3), 0);
+        assert_eq!(meter.pending(), 2);
+        assert_eq!(meter.ready(), 0);
+
+        // Attack beyond the meter is handed back
+        assert_eq!(meter.cancel(5), 3);
+        assert_eq!(meter.pending(), 0);
+    }
+}
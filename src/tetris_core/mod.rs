@@ -6,11 +6,24 @@ mod piece;
 mod game;
 mod rotation;
 mod randomizer;
+mod finesse;
+mod garbage;
+mod builder;
+mod stats;
+mod replay;
+mod placement;
 
 // Re-export the main components
-pub use board::{Board, Cell};
-pub use piece::PieceType;
-pub use game::{Game, GameState, ScoreSystem, TSpinType};
+pub use board::{Board, BoardError, BoardInvariantError, Cell, GravityMode, VisibleBoard};
+pub use piece::{PieceType, Rotation};
+pub use randomizer::{BagRandomizer, Randomizer, ScriptedRandomizer};
+pub use rotation::RotationSystem;
+pub use garbage::GarbageMeter;
+pub use game::{attack_lines, Game, GameConfig, GameOverReason, GameState, GoalMode, ScoreSystem, SpinRule, TSpinType};
+pub use builder::{BuildError, GameBuilder};
+pub use stats::GameStats;
+pub use replay::{Input, Replay, ReplayError, TimedInput};
+pub use placement::Move;
 
 // Constants for the game
 pub const BOARD_WIDTH: usize = 10;
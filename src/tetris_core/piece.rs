@@ -11,6 +11,33 @@ pub enum PieceType {
     L, // L-piece (orange)
 }
 
+impl PieceType {
+    /// Guideline display color as RGBA
+    pub fn color(self) -> [u8; 4] {
+        match self {
+            PieceType::I => [0, 255, 255, 255],
+            PieceType::O => [255, 255, 0, 255],
+            PieceType::T => [160, 0, 240, 255],
+            PieceType::S => [0, 240, 0, 255],
+            PieceType::Z => [240, 0, 0, 255],
+            PieceType::J => [0, 0, 240, 255],
+            PieceType::L => [240, 160, 0, 255],
+        }
+    }
+    
+    /// Converts the piece type to its index (0-6, in declaration order)
+    pub fn to_index(self) -> usize {
+        self as usize
+    }
+    
+    /// The piece type with the given index, or None if it's out of range
+    pub fn from_index(index: usize) -> Option<Self> {
+        [PieceType::I, PieceType::O, PieceType::T, PieceType::S, PieceType::Z, PieceType::J, PieceType::L]
+            .get(index)
+            .copied()
+    }
+}
+
 /// Represents a piece direction/orientation
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Rotation {
@@ -68,13 +95,8 @@ impl Piece {
     
     /// Get all block coordinates for this piece in its current position and rotation
     pub fn get_blocks(&self) -> Vec<(usize, usize)> {
-        let offsets = self.get_block_offsets();
-        
-        let blocks = offsets.iter()
-            .filter_map(|&(row_offset, col_offset)| {
-                let row = self.row + row_offset;
-                let col = self.col + col_offset;
-                
+        let blocks = self.get_block_positions().iter()
+            .filter_map(|&(row, col)| {
                 // Convert to usize, but only if non-negative
                 if row >= 0 && col >= 0 {
                     Some((row as usize, col as usize))
@@ -87,6 +109,36 @@ impl Piece {
         blocks
     }
     
+    /// Get all block coordinates as signed values, including any outside the board
+    pub fn get_block_positions(&self) -> [(i32, i32); 4] {
+        self.get_block_offsets()
+            .map(|(row_offset, col_offset)| (self.row + row_offset, self.col + col_offset))
+    }
+    
+    /// Get the rotation center as (row, col) board coordinates
+    /// I and O pieces rotate around a grid point, so their pivot is offset by half a cell
+    pub fn pivot(&self) -> (f32, f32) {
+        let (row, col) = (self.row as f32, self.col as f32);
+        match self.piece_type {
+            PieceType::I | PieceType::O => (row + 0.5, col + 0.5),
+            _ => (row, col),
+        }
+    }
+    
+    /// Get the occupancy of this piece's current rotation on a 4x4 grid
+    /// The shape is shifted to the top-left corner, independent of board position
+    pub fn normalized_grid(&self) -> [[bool; 4]; 4] {
+        let offsets = self.get_block_offsets();
+        let min_row = offsets.iter().map(|&(r, _)| r).min().unwrap();
+        let min_col = offsets.iter().map(|&(_, c)| c).min().unwrap();
+        
+        let mut grid = [[false; 4]; 4];
+        for &(row_offset, col_offset) in &offsets {
+            grid[(row_offset - min_row) as usize][(col_offset - min_col) as usize] = true;
+        }
+        grid
+    }
+    
     /// Get the block offsets for this piece in its current rotation
     fn get_block_offsets(&self) -> [(i32, i32); 4] {
         // These offsets follow the standard SRS (Super Rotation System) used in guideline Tetris
@@ -171,25 +223,27 @@ impl Piece {
         new_piece
     }
     
-    /// Creates a clone of this piece moved left
-    pub fn with_left_move(&self) -> Self {
+    /// Creates a clone of this piece shifted by `dr` rows and `dc` columns
+    pub fn translated(&self, dr: i32, dc: i32) -> Self {
         let mut new_piece = self.clone();
-        new_piece.move_left();
+        new_piece.row += dr;
+        new_piece.col += dc;
         new_piece
     }
     
+    /// Creates a clone of this piece moved left
+    pub fn with_left_move(&self) -> Self {
+        self.translated(0, -1)
+    }
+    
     /// Creates a clone of this piece moved right
     pub fn with_right_move(&self) -> Self {
-        let mut new_piece = self.clone();
-        new_piece.move_right();
-        new_piece
+        self.translated(0, 1)
     }
     
     /// Creates a clone of this piece moved down
     pub fn with_down_move(&self) -> Self {
-        let mut new_piece = self.clone();
-        new_piece.move_down();
-        new_piece
+        self.translated(1, 0)
     }
 }
 
@@ -202,4 +256,60 @@ impl Clone for Piece {
             rotation: self.rotation,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized_grid() {
+        // Position on the board doesn't matter
+        let o_piece = Piece::new(PieceType::O, 12, 7);
+        let grid = o_piece.normalized_grid();
+        
+        let filled: Vec<(usize, usize)> = (0..4)
+            .flat_map(|r| (0..4).map(move |c| (r, c)))
+            .filter(|&(r, c)| grid[r][c])
+            .collect();
+        assert_eq!(filled, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        
+        // A vertical I piece occupies one full column
+        let mut i_piece = Piece::new(PieceType::I, 3, 3);
+        i_piece.rotate_clockwise();
+        assert_eq!(i_piece.normalized_grid(), [[true, false, false, false]; 4]);
+    }
+    
+    #[test]
+    fn test_pivot() {
+        // The T piece rotates around its center cell
+        let t_piece = Piece::new(PieceType::T, 5, 4);
+        assert_eq!(t_piece.get_block_positions(), [(5, 4), (5, 3), (5, 5), (6, 4)]);
+        assert_eq!(t_piece.pivot(), (5.0, 4.0));
+        
+        // The O piece rotates around the middle of its 2x2 block
+        let o_piece = Piece::new(PieceType::O, 5, 4);
+        assert_eq!(o_piece.get_block_positions(), [(5, 4), (5, 5), (6, 4), (6, 5)]);
+        assert_eq!(o_piece.pivot(), (5.5, 4.5));
+        
+        // The I piece rotates around the middle of its 4x4 box, rows 4-7 and columns 3-6
+        let i_piece = Piece::new(PieceType::I, 5, 4);
+        assert_eq!(i_piece.get_block_positions(), [(5, 3), (5, 4), (5, 5), (5, 6)]);
+        assert_eq!(i_piece.pivot(), (5.5, 4.5));
+        let i_east = i_piece.with_clockwise_rotation();
+        assert_eq!(i_east.get_block_positions(), [(4, 5), (5, 5), (6, 5), (7, 5)]);
+        assert_eq!(i_east.pivot(), (5.5, 4.5));
+    }
+    
+    #[test]
+    fn test_translated() {
+        let piece = Piece::new(PieceType::T, 5, 6);
+        let moved = piece.translated(2, -3);
+        assert_eq!((moved.row, moved.col), (7, 3));
+        assert_eq!(moved.rotation, piece.rotation);
+        
+        for (&(row, col), &(moved_row, moved_col)) in piece.get_block_positions().iter().zip(moved.get_block_positions().iter()) {
+            assert_eq!((moved_row, moved_col), (row + 2, col - 3));
+        }
+    }
+}
@@ -0,0 +1,596 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::board::{Board, Cell};
+use super::game::{Game, GameOverReason, GameState};
+use super::piece::PieceType;
+use super::BOARD_WIDTH;
+
+// Most boards `Game::two_piece_continuations` returns
+const MAX_CONTINUATIONS: usize = 1000;
+
+/// A way to play the current piece: the inputs to perform, then the drop
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move {
+    /// Number of left movements to perform
+    pub left_moves: u8,
+    /// Number of right movements to perform
+    pub right_moves: u8,
+    /// Number of clockwise rotations to perform
+    pub clockwise_rotations: u8,
+    /// Number of counter-clockwise rotations to perform
+    pub counterclockwise_rotations: u8,
+    /// Whether to hard drop immediately
+    pub hard_drop: bool,
+    /// Whether to hold the piece
+    pub hold: bool,
+}
+
+impl Move {
+    /// Create a new move
+    pub fn new(
+        left_moves: u8,
+        right_moves: u8,
+        clockwise_rotations: u8,
+        counterclockwise_rotations: u8,
+        hard_drop: bool,
+        hold: bool,
+    ) -> Self {
+        Move {
+            left_moves,
+            right_moves,
+            clockwise_rotations,
+            counterclockwise_rotations,
+            hard_drop,
+            hold,
+        }
+    }
+
+    /// Number of button presses needed to perform this move (the drop itself not included)
+    /// A hold costs one press like any other input
+    pub fn input_count(&self) -> u32 {
+        self.left_moves as u32 +
+        self.right_moves as u32 +
+        self.clockwise_rotations as u32 +
+        self.counterclockwise_rotations as u32 +
+        self.hold as u32
+    }
+}
+
+impl Game {
+    /// Every candidate move for the current piece: holding (if allowed), then each number
+    /// of clockwise and counter-clockwise rotations combined with each target column
+    /// Some candidates may fail when applied, e.g. when a wall is in the way
+    pub fn candidate_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+
+        // Check if the current piece is valid
+        if self.current_piece.is_none() {
+            return moves;
+        }
+
+        // Without hard drop, placements are soft dropped to the floor instead
+        let hard_drop = self.config.hard_drop_enabled;
+
+        // Consider holding the piece first
+        if self.can_hold {
+            moves.push(Move::new(0, 0, 0, 0, hard_drop, true));
+        }
+
+        // Clockwise rotations first (including none), then counter-clockwise ones
+        let rotations = (0..4).map(|turns| (turns, 0)).chain((1..4).map(|turns| (0, turns)));
+        for (clockwise_rotations, counterclockwise_rotations) in rotations {
+            // For each rotation, try every possible horizontal position
+            for position in 0..BOARD_WIDTH {
+                let mut game_clone = self.clone();
+
+                // Apply rotations
+                for _ in 0..clockwise_rotations {
+                    if !game_clone.rotate_clockwise() {
+                        break;
+                    }
+                }
+                for _ in 0..counterclockwise_rotations {
+                    if !game_clone.rotate_counterclockwise() {
+                        break;
+                    }
+                }
+
+                // Get the horizontal position of the piece after rotation
+                let current_position = if let Some(ref piece) = game_clone.current_piece {
+                    piece.col as usize
+                } else {
+                    continue;
+                };
+
+                // Calculate the left or right moves needed to reach this position
+                let (left_moves, right_moves) = if position < current_position {
+                    ((current_position - position) as u8, 0)
+                } else {
+                    (0, (position - current_position) as u8)
+                };
+
+                moves.push(Move::new(
+                    left_moves,
+                    right_moves,
+                    clockwise_rotations,
+                    counterclockwise_rotations,
+                    hard_drop,
+                    false,
+                ));
+            }
+        }
+
+        moves
+    }
+
+    /// Perform a move: hold, rotate, shift and drop the current piece
+    /// Without hard drop the piece is soft dropped to the floor and left to lock
+    /// Returns false as soon as one of the inputs fails
+    pub fn apply_move(&mut self, move_to_apply: &Move) -> bool {
+        // Apply hold if needed
+        if move_to_apply.hold && self.can_hold && !self.hold_piece() {
+            return false;
+        }
+
+        // Apply rotations
+        for _ in 0..move_to_apply.clockwise_rotations {
+            if !self.rotate_clockwise() {
+                return false;
+            }
+        }
+
+        for _ in 0..move_to_apply.counterclockwise_rotations {
+            if !self.rotate_counterclockwise() {
+                return false;
+            }
+        }
+
+        // Apply horizontal movements
+        for _ in 0..move_to_apply.left_moves {
+            if !self.move_left() {
+                return false;
+            }
+        }
+
+        for _ in 0..move_to_apply.right_moves {
+            if !self.move_right() {
+                return false;
+            }
+        }
+
+        // Hard drop if needed
+        if move_to_apply.hard_drop {
+            if !self.hard_drop() {
+                return false;
+            }
+        } else if !self.config.hard_drop_enabled {
+            // Soft drop to the floor and wait out the lock delay
+            while self.move_down() {}
+            self.update(self.lock_delay());
+        }
+
+        true
+    }
+
+    /// Perform a move on a copy of the game, leaving this one untouched
+    /// Finesse isn't tracked on the copy, which keeps searches from paying for it on every lock,
+    /// and any entry delay is skipped so the copy already has its next piece to search with
+    /// Returns None if the move couldn't be carried out
+    pub fn apply_move_cloned(&self, move_to_apply: &Move) -> Option<Game> {
+        let mut game_clone = self.clone();
+        game_clone.config.track_finesse = false;
+        if !game_clone.apply_move(move_to_apply) {
+            return None;
+        }
+
+        if game_clone.is_in_are() {
+            game_clone.deal_next_piece();
+        }
+        Some(game_clone)
+    }
+
+    /// Block positions where the given moves (holds aside) bring the current piece to rest,
+    /// one entry per distinct placement; each entry is sorted
+    pub fn landing_positions_for(&self, moves: &[Move]) -> Vec<[(i32, i32); 4]> {
+        // Position pieces without dropping them, even in games that soft drop instead
+        let mut positioning_game = self.clone();
+        positioning_game.config.hard_drop_enabled = true;
+
+        let mut landings: Vec<[(i32, i32); 4]> = Vec::new();
+        for possible_move in moves {
+            if possible_move.hold {
+                continue;
+            }
+            let mut positioning = possible_move.clone();
+            positioning.hard_drop = false;
+
+            let mut positioned = positioning_game.clone();
+            if !positioned.apply_move(&positioning) {
+                continue;
+            }
+            let Some(mut landed) = positioned.current_piece.take() else {
+                continue;
+            };
+
+            // Drop the piece to where it would lock
+            while self.board.can_place(&landed.with_down_move()) {
+                landed = landed.with_down_move();
+            }
+
+            let mut blocks = landed.get_block_positions();
+            blocks.sort();
+            if !landings.contains(&blocks) {
+                landings.push(blocks);
+            }
+        }
+
+        landings
+    }
+
+    /// Enumerate the distinct placements of the current piece (without holding),
+    /// pairing each with the board it leaves once locked and any lines are cleared
+    pub fn placement_boards(&self) -> Vec<(Move, Board)> {
+        let mut placements: Vec<(Move, Board)> = Vec::new();
+
+        for possible_move in self.candidate_moves() {
+            if possible_move.hold {
+                continue;
+            }
+
+            // Simulate the move, skipping any that can't be carried out
+            let game_clone = match self.apply_move_cloned(&possible_move) {
+                Some(game_clone) => game_clone,
+                None => continue,
+            };
+
+            // Different input sequences often reach the same placement
+            if !placements.iter().any(|(_, board)| *board == game_clone.board) {
+                placements.push((possible_move, game_clone.board));
+            }
+        }
+
+        placements
+    }
+
+    /// Rows that at least one placement of the current piece would complete
+    pub fn clearable_rows(&self) -> Vec<usize> {
+        let Some(piece_type) = self.current_piece.as_ref().map(|piece| piece.piece_type) else {
+            return Vec::new();
+        };
+        let mut rows = Vec::new();
+
+        // Fill in each landing by hand so the full rows stay visible
+        for blocks in self.landing_positions_for(&self.candidate_moves()) {
+            let mut board = self.board.clone();
+            for &(row, col) in &blocks {
+                board.set_cell(row as usize, col as usize, Cell::Filled(piece_type));
+            }
+
+            for &(row, _) in &blocks {
+                let row = row as usize;
+                if board.is_line_complete(row) && !rows.contains(&row) {
+                    rows.push(row);
+                }
+            }
+        }
+
+        rows.sort();
+        rows
+    }
+
+    /// Pick one of the distinct placements of the current piece uniformly at random
+    /// None if the piece has nowhere to go
+    pub fn random_placement<R: Rng>(&self, rng: &mut R) -> Option<Move> {
+        self.placement_boards()
+            .choose(rng)
+            .map(|(placement, _)| placement.clone())
+    }
+
+    /// Piece types that could cover the given cell in some placement, as if each type in
+    /// turn were the current piece
+    pub fn pieces_reaching(&self, row: usize, col: usize) -> Vec<PieceType> {
+        let all_types = [
+            PieceType::I, PieceType::O, PieceType::T, PieceType::S,
+            PieceType::Z, PieceType::J, PieceType::L,
+        ];
+
+        all_types.into_iter()
+            .filter(|&piece_type| {
+                let mut game = self.clone();
+                game.set_current_piece(piece_type) &&
+                    game.landing_positions_for(&game.candidate_moves())
+                        .iter()
+                        .any(|blocks| blocks.contains(&(row as i32, col as i32)))
+            })
+            .collect()
+    }
+
+    /// Score a move would earn, found by playing it on a copy of the game
+    /// 0 if the move can't be carried out
+    pub fn preview_placement_score(&self, mv: &Move) -> u32 {
+        self.apply_move_cloned(mv)
+            .map_or(0, |after| after.score_system.score - self.score_system.score)
+    }
+
+    /// Distinct boards reachable by placing the current piece and then the first piece
+    /// of the next queue (without holding), capped at `MAX_CONTINUATIONS` boards
+    pub fn two_piece_continuations(&self) -> Vec<Board> {
+        let mut continuations: Vec<Board> = Vec::new();
+
+        for (first_move, _) in self.placement_boards() {
+            let after_first = match self.apply_move_cloned(&first_move) {
+                Some(game_clone) if game_clone.state == GameState::Playing => game_clone,
+                _ => continue,
+            };
+
+            for (_, board) in after_first.placement_boards() {
+                if continuations.len() >= MAX_CONTINUATIONS {
+                    return continuations;
+                }
+                if !continuations.contains(&board) {
+                    continuations.push(board);
+                }
+            }
+        }
+
+        continuations
+    }
+
+    /// Whether the board is past saving: the next piece can't spawn, or every placement
+    /// of it leaves the following piece blocked out
+    /// Lines the current piece might clear aren't taken into account, as with `Game::can_spawn_next`
+    pub fn is_terminal(&self) -> bool {
+        if self.state == GameState::GameOver {
+            return true;
+        }
+        if !self.can_spawn_next() {
+            return true;
+        }
+
+        // Deal the next piece in a lookahead game and try every placement of it
+        let mut lookahead = self.clone();
+        lookahead.deal_next_piece();
+
+        !lookahead.candidate_moves().iter()
+            .filter(|possible_move| !possible_move.hold)
+            .filter_map(|possible_move| lookahead.apply_move_cloned(possible_move))
+            .any(|game_clone| game_clone.game_over_reason() != Some(GameOverReason::BlockOut))
+    }
+
+    /// Garbage a move would send, found by playing it on a copy of the game, so aggressive
+    /// bots can rank moves by attack. Combo and back-to-back state count just as they would
+    /// for a real lock. 0 if the move can't be carried out
+    pub fn placement_attack(&self, mv: &Move) -> u32 {
+        self.apply_move_cloned(mv)
+            .map_or(0, |after| after.stats.attack_sent - self.stats.attack_sent)
+    }
+
+    /// Placements of the current piece and then the next queue pieces (without holding)
+    /// that end in a perfect clear, using at most `depth` pieces
+    /// The search tries every distinct placement at each step, so keep `depth` small
+    pub fn find_perfect_clear(&self, depth: usize) -> Option<Vec<Move>> {
+        if depth == 0 || self.state != GameState::Playing {
+            return None;
+        }
+
+        for (placement, board) in self.placement_boards() {
+            if board.is_perfect_clear() {
+                return Some(vec![placement]);
+            }
+
+            let after = match self.apply_move_cloned(&placement) {
+                Some(game_clone) => game_clone,
+                None => continue,
+            };
+            if let Some(mut rest) = after.find_perfect_clear(depth - 1) {
+                rest.insert(0, placement);
+                return Some(rest);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::time::Duration;
+    use crate::tetris_core::{GameConfig, Rotation, ScriptedRandomizer, BOARD_HEIGHT};
+
+    // Fill the given rows completely, except for the listed (row, col) cells
+    fn fill_rows_except(game: &mut Game, rows: std::ops::Range<usize>, empty: &[(usize, usize)]) {
+        for row in rows {
+            for col in 0..BOARD_WIDTH {
+                if !empty.contains(&(row, col)) {
+                    game.board.set_cell(row, col, Cell::Filled(PieceType::O));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_placement_boards() {
+        let mut game = Game::new();
+
+        // An O piece fits in 9 columns on an empty board
+        assert!(game.set_current_piece(PieceType::O));
+        let placements = game.placement_boards();
+        assert_eq!(placements.len(), BOARD_WIDTH - 1);
+
+        // A T piece has 8 + 9 + 8 + 9 placements across its four orientations
+        assert!(game.set_current_piece(PieceType::T));
+        let placements = game.placement_boards();
+        assert_eq!(placements.len(), 34);
+        for (i, (_, board)) in placements.iter().enumerate() {
+            assert!(placements[i + 1..].iter().all(|(_, other)| other != board));
+        }
+
+        // The original game is untouched
+        assert!(game.board.is_perfect_clear());
+    }
+
+    #[test]
+    fn test_clearable_rows() {
+        let mut game = Game::new();
+
+        // The bottom row is missing exactly the four cells of a flat I piece,
+        // and the row above is missing too much to be completed
+        fill_rows_except(&mut game, BOARD_HEIGHT - 1..BOARD_HEIGHT, &[
+            (BOARD_HEIGHT - 1, 3), (BOARD_HEIGHT - 1, 4), (BOARD_HEIGHT - 1, 5), (BOARD_HEIGHT - 1, 6),
+        ]);
+        for col in 0..3 {
+            game.board.set_cell(BOARD_HEIGHT - 2, col, Cell::Filled(PieceType::O));
+        }
+
+        assert!(game.set_current_piece(PieceType::I));
+        assert_eq!(game.clearable_rows(), vec![BOARD_HEIGHT - 1]);
+
+        // An O piece can't fill the gap
+        assert!(game.set_current_piece(PieceType::O));
+        assert!(game.clearable_rows().is_empty());
+
+        // Soft-drop games lock pieces when moved, which mustn't hide the cleared rows
+        game.config.hard_drop_enabled = false;
+        assert!(game.set_current_piece(PieceType::I));
+        assert_eq!(game.clearable_rows(), vec![BOARD_HEIGHT - 1]);
+    }
+
+    #[test]
+    fn test_random_placement() {
+        let game = Game::new();
+
+        let first = game.random_placement(&mut StdRng::seed_from_u64(7)).unwrap();
+        let second = game.random_placement(&mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(first, second);
+        assert!(!first.hold);
+
+        let mut over = game.clone();
+        over.force_game_over(GameOverReason::Forfeit);
+        assert!(over.random_placement(&mut StdRng::seed_from_u64(7)).is_none());
+    }
+
+    #[test]
+    fn test_pieces_reaching() {
+        let mut game = Game::new();
+
+        // Every piece can cover a cell on the floor of an open board
+        assert_eq!(game.pieces_reaching(BOARD_HEIGHT - 1, 4).len(), 7);
+
+        // Only an I piece reaches the bottom of a three-deep slot
+        let slot: Vec<(usize, usize)> = (BOARD_HEIGHT - 3..BOARD_HEIGHT).map(|row| (row, 4)).collect();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 3..BOARD_HEIGHT, &slot);
+        assert_eq!(game.pieces_reaching(BOARD_HEIGHT - 1, 4), vec![PieceType::I]);
+    }
+
+    #[test]
+    fn test_preview_placement_score() {
+        let mut game = Game::new();
+
+        // A T-spin double slot in column 4 with an overhang on the left
+        fill_rows_except(&mut game, BOARD_HEIGHT - 2..BOARD_HEIGHT, &[
+            (BOARD_HEIGHT - 2, 3), (BOARD_HEIGHT - 2, 4), (BOARD_HEIGHT - 2, 5), (BOARD_HEIGHT - 1, 4),
+        ]);
+        game.board.set_cell(BOARD_HEIGHT - 3, 3, Cell::Filled(PieceType::O));
+
+        // An upright T beside the slot spins in with one counter-clockwise rotation
+        assert!(game.set_current_piece(PieceType::T));
+        let t_piece = game.current_piece.as_mut().unwrap();
+        t_piece.row = BOARD_HEIGHT as i32 - 2;
+        t_piece.rotation = Rotation::East;
+        let spin = Move::new(0, 0, 0, 1, true, false);
+
+        let preview = game.preview_placement_score(&spin);
+        let before = game.score_system.score;
+        assert!(game.apply_move(&spin));
+        assert_eq!(game.score_system.lines_cleared, 2);
+        assert_eq!(preview, game.score_system.score - before);
+        assert!(preview >= 1200);
+    }
+
+    #[test]
+    fn test_two_piece_continuations() {
+        let script = [PieceType::O, PieceType::I, PieceType::T];
+        let game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+
+        // At most nine O placements, each followed by one of seventeen I placements
+        let continuations = game.two_piece_continuations();
+        assert!(continuations.len() > 17 && continuations.len() <= 9 * 17);
+        for (index, board) in continuations.iter().enumerate() {
+            assert!(!continuations[index + 1..].contains(board));
+        }
+
+        // An entry delay doesn't hide the second piece from the search
+        let mut delayed = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        delayed.set_are(Duration::from_millis(100));
+        assert_eq!(delayed.two_piece_continuations(), continuations);
+    }
+
+    #[test]
+    fn test_is_terminal() {
+        let script = [PieceType::T, PieceType::O, PieceType::L, PieceType::J];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        assert!(!game.is_terminal());
+
+        // With the board filled to the top nothing can spawn
+        fill_rows_except(&mut game, 0..BOARD_HEIGHT, &[]);
+        assert!(game.is_terminal());
+    }
+
+    #[test]
+    fn test_placement_attack() {
+        let script = [PieceType::I, PieceType::O];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+        let best_attack = |game: &Game| {
+            game.candidate_moves().iter()
+                .map(|possible_move| game.placement_attack(possible_move))
+                .max()
+                .unwrap_or(0)
+        };
+
+        // A well in column 9 over a single open row: the best the I can do is a single.
+        // A stray cell on the left keeps either clear from being a perfect clear
+        fill_rows_except(&mut game, BOARD_HEIGHT - 1..BOARD_HEIGHT, &[(BOARD_HEIGHT - 1, 9)]);
+        game.board.set_cell(BOARD_HEIGHT - 9, 0, Cell::Filled(PieceType::O));
+        let single_attack = best_attack(&game);
+
+        // Four rows deep, the I can score a Tetris
+        let well: Vec<(usize, usize)> = (BOARD_HEIGHT - 8..BOARD_HEIGHT).map(|row| (row, 9)).collect();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 8..BOARD_HEIGHT, &well);
+        assert_eq!(best_attack(&game), 4);
+
+        // After an earlier Tetris the next one is back-to-back
+        assert!(game.place_piece_at(PieceType::I, BOARD_HEIGHT - 3, 9, Rotation::West));
+        assert!(game.is_back_to_back());
+        let attack_sent = game.stats.attack_sent;
+        let tetris_attack = best_attack(&game);
+        assert_eq!(tetris_attack, 5);
+        assert!(tetris_attack > single_attack);
+
+        // Previewing leaves the game untouched
+        assert_eq!(game.stats.attack_sent, attack_sent);
+        assert_eq!(game.stats.lines_cleared(), 4);
+    }
+
+    #[test]
+    fn test_find_perfect_clear() {
+        let script = [PieceType::O, PieceType::O, PieceType::T];
+        let mut game = Game::with_randomizer(GameConfig::default(), Box::new(ScriptedRandomizer::new(&script)));
+
+        // A 4x2 gap at the left of the bottom two rows takes two O pieces to fill
+        let gap: Vec<(usize, usize)> = (BOARD_HEIGHT - 2..BOARD_HEIGHT)
+            .flat_map(|row| (0..4).map(move |col| (row, col)))
+            .collect();
+        fill_rows_except(&mut game, BOARD_HEIGHT - 2..BOARD_HEIGHT, &gap);
+        assert!(game.find_perfect_clear(1).is_none());
+
+        let moves = game.find_perfect_clear(2).unwrap();
+        assert_eq!(moves.len(), 2);
+        for pc_move in &moves {
+            assert!(game.apply_move(pc_move));
+        }
+        assert!(game.board.is_perfect_clear());
+    }
+}
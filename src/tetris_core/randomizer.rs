@@ -1,15 +1,26 @@
 use std::collections::VecDeque;
-use rand::{thread_rng, Rng, seq::SliceRandom};
+use rand::{SeedableRng, seq::SliceRandom};
+use rand::rngs::StdRng;
 use super::piece::PieceType;
 
+// Number of pieces kept in the preview queue
+const PREVIEW_SIZE: usize = 5;
+
 /// Trait for piece randomizers in Tetris
 pub trait Randomizer {
-    /// Get the next piece from the randomizer
-    fn next(&mut self) -> PieceType;
+    /// Get the next piece from the randomizer, or None once it has run out of pieces
+    fn next(&mut self) -> Option<PieceType>;
     
     /// Peek at the next n pieces without consuming them
     fn peek(&self, count: usize) -> Vec<PieceType>;
     
+    /// Return a piece obtained from `next` so that it is dealt again
+    /// Randomizers that can't rewind keep the default, which leaves the sequence unchanged
+    fn unget(&mut self, _piece: PieceType) {}
+    
+    /// Restart the sequence from a fresh state (seeded randomizers replay their seed)
+    fn reset(&mut self);
+    
     /// Clone this randomizer (required for Game cloning)
     fn clone_box(&self) -> Box<dyn Randomizer>;
 }
@@ -21,34 +32,48 @@ pub struct BagRandomizer {
     bag: Vec<PieceType>,
     // Queue of pieces that have been generated but not yet consumed
     preview_queue: VecDeque<PieceType>,
+    // Random source used to shuffle each bag
+    rng: StdRng,
+    // Seed to restart from on reset, if this randomizer is seeded
+    seed: Option<u64>,
 }
 
 impl BagRandomizer {
     /// Creates a new 7-bag randomizer
     pub fn new() -> Self {
-        let mut rng = thread_rng();
+        Self::from_rng(StdRng::from_entropy(), None)
+    }
+    
+    /// Creates a 7-bag randomizer that always deals the same sequence for a given seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed), Some(seed))
+    }
+    
+    fn from_rng(rng: StdRng, seed: Option<u64>) -> Self {
         let mut randomizer = BagRandomizer {
             bag: vec![],
             preview_queue: VecDeque::new(),
+            rng,
+            seed,
         };
-        
-        // Generate initial bag
-        randomizer.refill_bag(&mut rng);
-        
-        // Fill preview queue
-        for _ in 0..5 {
-            if randomizer.bag.is_empty() {
-                randomizer.refill_bag(&mut rng);
+        randomizer.fill_preview();
+        randomizer
+    }
+    
+    /// Fills the preview queue up to its regular size, opening new bags as needed
+    fn fill_preview(&mut self) {
+        while self.preview_queue.len() < PREVIEW_SIZE {
+            if self.bag.is_empty() {
+                self.refill_bag();
             }
             
-            randomizer.preview_queue.push_back(randomizer.bag.pop().unwrap());
+            // Add a new piece to the back of the queue
+            self.preview_queue.push_back(self.bag.pop().unwrap());
         }
-        
-        randomizer
     }
     
     /// Refills the internal bag with one of each piece type, randomly ordered
-    fn refill_bag(&mut self, rng: &mut impl Rng) {
+    fn refill_bag(&mut self) {
         self.bag = vec![
             PieceType::I,
             PieceType::O,
@@ -58,7 +83,15 @@ impl BagRandomizer {
             PieceType::J,
             PieceType::L,
         ];
-        self.bag.shuffle(rng);
+        self.bag.shuffle(&mut self.rng);
+    }
+    
+    /// Forces the given pieces to be dealt next, ahead of the bag pieces
+    /// Once they have been consumed the regular bag sequence resumes
+    pub fn prime_with(&mut self, pieces: &[PieceType]) {
+        for &piece in pieces.iter().rev() {
+            self.preview_queue.push_front(piece);
+        }
     }
 }
 
@@ -67,23 +100,19 @@ impl Clone for BagRandomizer {
         BagRandomizer {
             bag: self.bag.clone(),
             preview_queue: self.preview_queue.clone(),
+            rng: self.rng.clone(),
+            seed: self.seed,
         }
     }
 }
 
 impl Randomizer for BagRandomizer {
-    fn next(&mut self) -> PieceType {
-        // Take the next piece from the queue
-        let next_piece = self.preview_queue.pop_front().unwrap();
-        
-        // Get a new piece for the preview
-        let mut rng = thread_rng();
-        if self.bag.is_empty() {
-            self.refill_bag(&mut rng);
-        }
+    fn next(&mut self) -> Option<PieceType> {
+        // Take the next piece from the queue, which is never empty since bags refill endlessly
+        let next_piece = self.preview_queue.pop_front();
         
-        // Add a new piece to the back of the queue
-        self.preview_queue.push_back(self.bag.pop().unwrap());
+        // Top the preview back up (primed pieces may have made it longer)
+        self.fill_preview();
         
         next_piece
     }
@@ -95,7 +124,156 @@ impl Randomizer for BagRandomizer {
             .collect()
     }
     
+    fn unget(&mut self, piece: PieceType) {
+        self.preview_queue.push_front(piece);
+        
+        // Hand the last preview piece back to the bag so the queue keeps its size
+        // and the dealt sequence stays exactly the same
+        if self.preview_queue.len() > PREVIEW_SIZE {
+            let last = self.preview_queue.pop_back().unwrap();
+            self.bag.push(last);
+        }
+    }
+    
+    fn reset(&mut self) {
+        self.bag.clear();
+        self.preview_queue.clear();
+        self.rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.fill_preview();
+    }
+    
     fn clone_box(&self) -> Box<dyn Randomizer> {
         Box::new(self.clone())
     }
-}
\ No newline at end of file
+}
+
+/// A randomizer that deals a fixed list of pieces and then runs out
+/// Useful for puzzles and tests that need an exact piece sequence
+pub struct ScriptedRandomizer {
+    // The full script, kept so the sequence can be restarted
+    script: Vec<PieceType>,
+    // Pieces still to be dealt
+    remaining: VecDeque<PieceType>,
+}
+
+impl ScriptedRandomizer {
+    /// Creates a randomizer that deals exactly the given pieces in order
+    pub fn new(pieces: &[PieceType]) -> Self {
+        ScriptedRandomizer {
+            script: pieces.to_vec(),
+            remaining: pieces.iter().copied().collect(),
+        }
+    }
+}
+
+impl Clone for ScriptedRandomizer {
+    fn clone(&self) -> Self {
+        ScriptedRandomizer {
+            script: self.script.clone(),
+            remaining: self.remaining.clone(),
+        }
+    }
+}
+
+impl Randomizer for ScriptedRandomizer {
+    fn next(&mut self) -> Option<PieceType> {
+        self.remaining.pop_front()
+    }
+    
+    fn peek(&self, count: usize) -> Vec<PieceType> {
+        self.remaining.iter().take(count).cloned().collect()
+    }
+    
+    fn unget(&mut self, piece: PieceType) {
+        self.remaining.push_front(piece);
+    }
+    
+    fn reset(&mut self) {
+        self.remaining = self.script.iter().copied().collect();
+    }
+    
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prime_with_forces_next_pieces() {
+        let mut randomizer = BagRandomizer::new();
+        randomizer.prime_with(&[PieceType::T, PieceType::I]);
+        
+        assert_eq!(randomizer.next(), Some(PieceType::T));
+        assert_eq!(randomizer.next(), Some(PieceType::I));
+        
+        // The following seven pieces are the untouched first bag
+        let bag: Vec<PieceType> = (0..7).filter_map(|_| randomizer.next()).collect();
+        for piece_type in [PieceType::I, PieceType::O, PieceType::T, PieceType::S, PieceType::Z, PieceType::J, PieceType::L] {
+            assert!(bag.contains(&piece_type));
+        }
+        assert_eq!(randomizer.peek(10).len(), PREVIEW_SIZE);
+    }
+
+    #[test]
+    fn test_unget_rewinds_one_piece() {
+        let mut randomizer = BagRandomizer::new();
+        let mut reference = randomizer.clone();
+        
+        let piece = randomizer.next().unwrap();
+        randomizer.unget(piece);
+        assert_eq!(randomizer.peek(PREVIEW_SIZE), reference.peek(PREVIEW_SIZE));
+        
+        // The rewound randomizer deals the same bag as one that never advanced
+        for _ in 0..7 {
+            assert_eq!(randomizer.next(), reference.next());
+        }
+    }
+
+    #[test]
+    fn test_unget_defaults_to_no_rewind() {
+        // A randomizer written before `unget` existed still builds and deals as before
+        struct AlwaysT;
+        impl Randomizer for AlwaysT {
+            fn next(&mut self) -> Option<PieceType> { Some(PieceType::T) }
+            fn peek(&self, count: usize) -> Vec<PieceType> { vec![PieceType::T; count] }
+            fn reset(&mut self) {}
+            fn clone_box(&self) -> Box<dyn Randomizer> { Box::new(AlwaysT) }
+        }
+        
+        let mut randomizer = AlwaysT;
+        randomizer.unget(PieceType::I);
+        assert_eq!(randomizer.next(), Some(PieceType::T));
+    }
+
+    #[test]
+    fn test_seeded_reset_replays_sequence() {
+        let mut randomizer = BagRandomizer::with_seed(7);
+        let first_run: Vec<Option<PieceType>> = (0..20).map(|_| randomizer.next()).collect();
+        
+        randomizer.reset();
+        let second_run: Vec<Option<PieceType>> = (0..20).map(|_| randomizer.next()).collect();
+        assert_eq!(first_run, second_run);
+        
+        // Another randomizer with the same seed deals the same pieces
+        let mut same_seed = BagRandomizer::with_seed(7);
+        assert_eq!(same_seed.next(), first_run[0]);
+    }
+
+    #[test]
+    fn test_scripted_randomizer_runs_out() {
+        let mut randomizer = ScriptedRandomizer::new(&[PieceType::S, PieceType::Z]);
+        assert_eq!(randomizer.peek(5), vec![PieceType::S, PieceType::Z]);
+        assert_eq!(randomizer.next(), Some(PieceType::S));
+        assert_eq!(randomizer.next(), Some(PieceType::Z));
+        assert_eq!(randomizer.next(), None);
+        
+        randomizer.reset();
+        assert_eq!(randomizer.next(), Some(PieceType::S));
+    }
+}
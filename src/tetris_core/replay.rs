@@ -0,0 +1,602 @@
+use std::fmt;
+use std::time::Duration;
+use super::game::{Game, GameConfig, GoalMode, SpinRule};
+
+// Version written into exported replays; bump whenever the format changes
+const REPLAY_FORMAT_VERSION: u64 = 1;
+
+/// A single player action
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Input {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    RotateClockwise,
+    RotateCounterclockwise,
+    HardDrop,
+    Hold,
+}
+
+impl Input {
+    /// Name used for the input in exported replays
+    fn name(self) -> &'static str {
+        match self {
+            Input::MoveLeft => "move_left",
+            Input::MoveRight => "move_right",
+            Input::SoftDrop => "soft_drop",
+            Input::RotateClockwise => "rotate_clockwise",
+            Input::RotateCounterclockwise => "rotate_counterclockwise",
+            Input::HardDrop => "hard_drop",
+            Input::Hold => "hold",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Input::MoveLeft,
+            Input::MoveRight,
+            Input::SoftDrop,
+            Input::RotateClockwise,
+            Input::RotateCounterclockwise,
+            Input::HardDrop,
+            Input::Hold,
+        ]
+        .into_iter()
+        .find(|input| input.name() == name)
+    }
+
+    /// Perform the input on a game
+    pub fn apply(self, game: &mut Game) -> bool {
+        match self {
+            Input::MoveLeft => game.move_left(),
+            Input::MoveRight => game.move_right(),
+            Input::SoftDrop => game.move_down(),
+            Input::RotateClockwise => game.rotate_clockwise(),
+            Input::RotateCounterclockwise => game.rotate_counterclockwise(),
+            Input::HardDrop => game.hard_drop(),
+            Input::Hold => game.hold_piece(),
+        }
+    }
+}
+
+/// An input together with the time it was made, measured from the start of the game
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TimedInput {
+    pub time_ms: u64,
+    pub input: Input,
+}
+
+/// Errors from loading a replay
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    /// The text isn't a replay in the expected format
+    Malformed(String),
+    /// The replay was written by a format version this build doesn't understand
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Malformed(reason) => write!(f, "malformed replay: {}", reason),
+            ReplayError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported replay format version {} (expected {})",
+                version, REPLAY_FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// A recorded game: the match seed, the rules it was played by and every input made, in order
+#[derive(Debug, PartialEq, Clone)]
+pub struct Replay {
+    pub seed: u64,
+    pub config: GameConfig,
+    pub inputs: Vec<TimedInput>,
+}
+
+impl Replay {
+    /// Create an empty replay for a game started with `Game::new_match(seed)`
+    pub fn new(seed: u64) -> Self {
+        Self::with_config(seed, GameConfig::default())
+    }
+
+    /// Create an empty replay for a game started with `Game::new_match_with_config(seed, config)`
+    pub fn with_config(seed: u64, config: GameConfig) -> Self {
+        Replay {
+            seed,
+            config,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Record an input made `time_ms` milliseconds into the game
+    pub fn record(&mut self, time_ms: u64, input: Input) {
+        self.inputs.push(TimedInput { time_ms, input });
+    }
+
+    /// Replay the inputs on a fresh game, advancing time between them
+    pub fn play(&self) -> Game {
+        let mut game = Game::new_match_with_config(self.seed, self.config);
+        let mut now_ms = 0;
+
+        for timed in &self.inputs {
+            if timed.time_ms > now_ms {
+                game.update(Duration::from_millis(timed.time_ms - now_ms));
+                now_ms = timed.time_ms;
+            }
+            timed.input.apply(&mut game);
+        }
+
+        game
+    }
+
+    /// Export the replay as versioned JSON
+    /// Durations in the config are written in whole milliseconds
+    pub fn to_json(&self) -> String {
+        let inputs: Vec<String> = self.inputs.iter()
+            .map(|timed| format!("{{\"time_ms\":{},\"input\":\"{}\"}}", timed.time_ms, timed.input.name()))
+            .collect();
+
+        format!(
+            "{{\"version\":{},\"seed\":{},\"config\":{},\"inputs\":[{}]}}",
+            REPLAY_FORMAT_VERSION,
+            self.seed,
+            config_to_json(&self.config),
+            inputs.join(",")
+        )
+    }
+
+    /// Load a replay exported by `to_json`
+    /// Fields, inputs and rule names this build doesn't know are rejected as malformed
+    pub fn from_json(json: &str) -> Result<Replay, ReplayError> {
+        let mut parser = JsonParser::new(json);
+        let value = parser.parse_value()?;
+        parser.expect_end()?;
+
+        let version = value.field("version")?.as_number()?;
+        if version != REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        value.expect_fields(&["version", "seed", "config", "inputs"])?;
+
+        let seed = value.field("seed")?.as_number()?;
+        let config = config_from_json(value.field("config")?)?;
+        let inputs = value.field("inputs")?.as_array()?
+            .iter()
+            .map(|entry| {
+                entry.expect_fields(&["time_ms", "input"])?;
+                let time_ms = entry.field("time_ms")?.as_number()?;
+                let name = entry.field("input")?.as_string()?;
+                let input = Input::from_name(name)
+                    .ok_or_else(|| ReplayError::Malformed(format!("unknown input \"{}\"", name)))?;
+                Ok(TimedInput { time_ms, input })
+            })
+            .collect::<Result<Vec<_>, ReplayError>>()?;
+
+        Ok(Replay { seed, config, inputs })
+    }
+}
+
+// Names of the config fields, in the order `config_to_json` writes them
+const CONFIG_FIELDS: [&str; 17] = [
+    "total_hold_limit", "countdown_ms", "hard_drop_enabled", "max_lock_resets",
+    "gravity_enabled", "garbage_delay_ms", "piece_gravity_multipliers", "reset_lock_on_failed_input",
+    "strict_tspin_corners", "are_ms", "starting_level", "lock_delay_ms", "preview_length", "goal",
+    "spin_rule", "track_finesse", "solid_ceiling",
+];
+
+fn config_to_json(config: &GameConfig) -> String {
+    let optional = |value: Option<u64>| value.map_or("null".to_string(), |value| value.to_string());
+    let multipliers: Vec<String> = config.piece_gravity_multipliers.iter()
+        .map(|multiplier| multiplier.to_string())
+        .collect();
+    let goal = match config.goal {
+        GoalMode::Endless => "\"endless\"".to_string(),
+        GoalMode::Sprint(lines) => format!("{{\"sprint\":{}}}", lines),
+    };
+    let spin_rule = match config.spin_rule {
+        SpinRule::TSpinOnly => "t_spin_only",
+        SpinRule::AllImmobile => "all_immobile",
+        SpinRule::AllSpinMini => "all_spin_mini",
+    };
+
+    let values = [
+        optional(config.total_hold_limit.map(u64::from)),
+        optional(config.countdown.map(|countdown| countdown.as_millis() as u64)),
+        config.hard_drop_enabled.to_string(),
+        config.max_lock_resets.to_string(),
+        config.gravity_enabled.to_string(),
+        config.garbage_delay.as_millis().to_string(),
+        format!("[{}]", multipliers.join(",")),
+        config.reset_lock_on_failed_input.to_string(),
+        config.strict_tspin_corners.to_string(),
+        config.are.as_millis().to_string(),
+        config.starting_level.to_string(),
+        config.lock_delay.as_millis().to_string(),
+        config.preview_length.to_string(),
+        goal,
+        format!("\"{}\"", spin_rule),
+        config.track_finesse.to_string(),
+        config.solid_ceiling.to_string(),
+    ];
+    let fields: Vec<String> = CONFIG_FIELDS.iter()
+        .zip(values)
+        .map(|(name, value)| format!("\"{}\":{}", name, value))
+        .collect();
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn config_from_json(value: &JsonValue) -> Result<GameConfig, ReplayError> {
+    value.expect_fields(&CONFIG_FIELDS)?;
+    let optional_number = |name: &str| -> Result<Option<u64>, ReplayError> {
+        match value.field(name)? {
+            JsonValue::Null => Ok(None),
+            number => number.as_number().map(Some),
+        }
+    };
+    let small_number = |name: &str| -> Result<u32, ReplayError> {
+        let number = value.field(name)?.as_number()?;
+        u32::try_from(number).map_err(|_| ReplayError::Malformed(format!("\"{}\" is out of range", name)))
+    };
+    let millis = |name: &str| -> Result<Duration, ReplayError> {
+        value.field(name)?.as_number().map(Duration::from_millis)
+    };
+
+    let total_hold_limit = match optional_number("total_hold_limit")? {
+        Some(limit) => Some(u32::try_from(limit)
+            .map_err(|_| ReplayError::Malformed("\"total_hold_limit\" is out of range".to_string()))?),
+        None => None,
+    };
+    let max_lock_resets = u8::try_from(small_number("max_lock_resets")?)
+        .map_err(|_| ReplayError::Malformed("\"max_lock_resets\" is out of range".to_string()))?;
+
+    let multiplier_values = value.field("piece_gravity_multipliers")?.as_array()?;
+    let mut piece_gravity_multipliers = [1.0; 7];
+    if multiplier_values.len() != piece_gravity_multipliers.len() {
+        return Err(ReplayError::Malformed("expected 7 piece gravity multipliers".to_string()));
+    }
+    for (multiplier, value) in piece_gravity_multipliers.iter_mut().zip(multiplier_values) {
+        *multiplier = value.as_float()?;
+    }
+
+    let goal = match value.field("goal")? {
+        JsonValue::String(name) if name == "endless" => GoalMode::Endless,
+        goal @ JsonValue::Object(_) => {
+            goal.expect_fields(&["sprint"])?;
+            let lines = goal.field("sprint")?.as_number()?;
+            GoalMode::Sprint(u32::try_from(lines)
+                .map_err(|_| ReplayError::Malformed("sprint line count is out of range".to_string()))?)
+        }
+        _ => return Err(ReplayError::Malformed("unknown goal".to_string())),
+    };
+    let spin_rule = match value.field("spin_rule")?.as_string()? {
+        "t_spin_only" => SpinRule::TSpinOnly,
+        "all_immobile" => SpinRule::AllImmobile,
+        "all_spin_mini" => SpinRule::AllSpinMini,
+        name => return Err(ReplayError::Malformed(format!("unknown spin rule \"{}\"", name))),
+    };
+
+    Ok(GameConfig {
+        total_hold_limit,
+        countdown: optional_number("countdown_ms")?.map(Duration::from_millis),
+        hard_drop_enabled: value.field("hard_drop_enabled")?.as_bool()?,
+        max_lock_resets,
+        gravity_enabled: value.field("gravity_enabled")?.as_bool()?,
+        garbage_delay: millis("garbage_delay_ms")?,
+        piece_gravity_multipliers,
+        reset_lock_on_failed_input: value.field("reset_lock_on_failed_input")?.as_bool()?,
+        strict_tspin_corners: value.field("strict_tspin_corners")?.as_bool()?,
+        are: millis("are_ms")?,
+        starting_level: small_number("starting_level")?,
+        lock_delay: millis("lock_delay_ms")?,
+        preview_length: value.field("preview_length")?.as_number()? as usize,
+        goal,
+        spin_rule,
+        track_finesse: value.field("track_finesse")?.as_bool()?,
+        solid_ceiling: value.field("solid_ceiling")?.as_bool()?,
+    })
+}
+
+/// The subset of JSON values used by the replay format
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(u64),
+    // Any number that isn't an unsigned integer
+    Float(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    fn field(&self, key: &str) -> Result<&JsonValue, ReplayError> {
+        match self {
+            JsonValue::Object(fields) => fields.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| ReplayError::Malformed(format!("missing field \"{}\"", key))),
+            _ => Err(ReplayError::Malformed("expected an object".to_string())),
+        }
+    }
+
+    // Fails on the first field that isn't in `known`
+    fn expect_fields(&self, known: &[&str]) -> Result<(), ReplayError> {
+        match self {
+            JsonValue::Object(fields) => match fields.iter().find(|(name, _)| !known.contains(&name.as_str())) {
+                Some((name, _)) => Err(ReplayError::Malformed(format!("unknown field \"{}\"", name))),
+                None => Ok(()),
+            },
+            _ => Err(ReplayError::Malformed("expected an object".to_string())),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, ReplayError> {
+        match self {
+            JsonValue::Bool(value) => Ok(*value),
+            _ => Err(ReplayError::Malformed("expected true or false".to_string())),
+        }
+    }
+
+    fn as_float(&self) -> Result<f64, ReplayError> {
+        match self {
+            JsonValue::Number(number) => Ok(*number as f64),
+            JsonValue::Float(number) => Ok(*number),
+            _ => Err(ReplayError::Malformed("expected a number".to_string())),
+        }
+    }
+
+    fn as_number(&self) -> Result<u64, ReplayError> {
+        match self {
+            JsonValue::Number(number) => Ok(*number),
+            _ => Err(ReplayError::Malformed("expected a number".to_string())),
+        }
+    }
+
+    fn as_string(&self) -> Result<&str, ReplayError> {
+        match self {
+            JsonValue::String(string) => Ok(string),
+            _ => Err(ReplayError::Malformed("expected a string".to_string())),
+        }
+    }
+
+    fn as_array(&self) -> Result<&[JsonValue], ReplayError> {
+        match self {
+            JsonValue::Array(values) => Ok(values),
+            _ => Err(ReplayError::Malformed("expected an array".to_string())),
+        }
+    }
+}
+
+/// Minimal JSON reader covering objects, arrays, plain strings, numbers, booleans and null
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        JsonParser {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ReplayError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(ReplayError::Malformed(format!("expected '{}' but found '{}'", expected, c))),
+            None => Err(ReplayError::Malformed(format!("expected '{}' but the text ended", expected))),
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), ReplayError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            None => Ok(()),
+            Some(c) => Err(ReplayError::Malformed(format!("unexpected '{}' after the replay", c))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, ReplayError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string().map(JsonValue::String),
+            Some(c) if c.is_ascii_digit() || *c == '-' => self.parse_number(),
+            Some(c) if c.is_ascii_alphabetic() => self.parse_literal(),
+            Some(&c) => Err(ReplayError::Malformed(format!("unexpected '{}'", c))),
+            None => Err(ReplayError::Malformed("unexpected end of text".to_string())),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, ReplayError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.chars.next();
+            return Ok(JsonValue::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            fields.push((key, self.parse_value()?));
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(fields)),
+                _ => return Err(ReplayError::Malformed("expected ',' or '}' in object".to_string())),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, ReplayError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+            return Ok(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(values)),
+                _ => return Err(ReplayError::Malformed("expected ',' or ']' in array".to_string())),
+            }
+        }
+    }
+
+    // Escapes never appear in replay files, so they aren't supported
+    fn parse_string(&mut self) -> Result<String, ReplayError> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => return Err(ReplayError::Malformed("escaped characters aren't supported".to_string())),
+                Some(c) => string.push(c),
+                None => return Err(ReplayError::Malformed("unterminated string".to_string())),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, ReplayError> {
+        let mut digits = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                break;
+            }
+            digits.push(c);
+            self.chars.next();
+        }
+
+        if digits.chars().all(|c| c.is_ascii_digit()) {
+            return digits.parse()
+                .map(JsonValue::Number)
+                .map_err(|_| ReplayError::Malformed(format!("number {} is out of range", digits)));
+        }
+        digits.parse()
+            .map(JsonValue::Float)
+            .map_err(|_| ReplayError::Malformed(format!("invalid number {}", digits)))
+    }
+
+    fn parse_literal(&mut self) -> Result<JsonValue, ReplayError> {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_alphabetic() {
+                break;
+            }
+            word.push(c);
+            self.chars.next();
+        }
+
+        match word.as_str() {
+            "true" => Ok(JsonValue::Bool(true)),
+            "false" => Ok(JsonValue::Bool(false)),
+            "null" => Ok(JsonValue::Null),
+            _ => Err(ReplayError::Malformed(format!("unexpected \"{}\"", word))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut replay = Replay::new(1234);
+        replay.record(0, Input::MoveLeft);
+        replay.record(120, Input::RotateClockwise);
+        replay.record(300, Input::HardDrop);
+        replay.record(900, Input::Hold);
+        replay.record(1500, Input::MoveRight);
+        replay.record(1600, Input::HardDrop);
+
+        let loaded = Replay::from_json(&replay.to_json()).unwrap();
+        assert_eq!(loaded, replay);
+        assert_eq!(loaded.play().board, replay.play().board);
+        assert!(!replay.play().board.is_perfect_clear());
+    }
+
+    #[test]
+    fn test_json_round_trip_keeps_config() {
+        let config = GameConfig {
+            total_hold_limit: Some(3),
+            countdown: Some(Duration::from_millis(3000)),
+            piece_gravity_multipliers: [1.0, 1.5, 1.0, 0.25, 1.0, 1.0, 2.0],
+            strict_tspin_corners: false,
+            are: Duration::from_millis(100),
+            preview_length: 3,
+            goal: GoalMode::Sprint(40),
+            spin_rule: SpinRule::AllImmobile,
+            ..GameConfig::default()
+        };
+        let mut replay = Replay::with_config(99, config);
+        replay.record(3000, Input::HardDrop);
+        replay.record(3250, Input::HardDrop);
+
+        let loaded = Replay::from_json(&replay.to_json()).unwrap();
+        assert_eq!(loaded.config, config);
+        assert_eq!(loaded, replay);
+
+        // The countdown and entry delay come back with the config, so both drops land
+        let game = loaded.play();
+        assert_eq!(game.config, config);
+        assert_eq!(game.board, replay.play().board);
+        assert_eq!(game.stats.pieces_placed, 2);
+    }
+
+    #[test]
+    fn test_rejects_unrecognised_input() {
+        let mut replay = Replay::new(1);
+        replay.record(0, Input::HardDrop);
+        let json = replay.to_json();
+        assert!(Replay::from_json(&json).is_ok());
+
+        let extra_field = json.replacen("\"seed\"", "\"speed\":1,\"seed\"", 1);
+        assert_eq!(Replay::from_json(&extra_field), Err(ReplayError::Malformed("unknown field \"speed\"".to_string())));
+
+        let extra_config = json.replacen("\"are_ms\"", "\"das_ms\":100,\"are_ms\"", 1);
+        assert!(matches!(Replay::from_json(&extra_config), Err(ReplayError::Malformed(_))));
+
+        let unknown_rule = json.replacen("t_spin_only", "all_spin", 1);
+        assert!(matches!(Replay::from_json(&unknown_rule), Err(ReplayError::Malformed(_))));
+
+        let unknown_input = json.replacen("hard_drop\"", "teleport\"", 1);
+        assert!(matches!(Replay::from_json(&unknown_input), Err(ReplayError::Malformed(_))));
+
+        let missing_config = "{\"version\":1,\"seed\":1,\"inputs\":[]}";
+        assert!(matches!(Replay::from_json(missing_config), Err(ReplayError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let json = "{\"version\": 99, \"seed\": 1, \"inputs\": []}";
+        assert_eq!(Replay::from_json(json), Err(ReplayError::UnsupportedVersion(99)));
+        assert!(matches!(Replay::from_json("{\"version\": 1"), Err(ReplayError::Malformed(_))));
+    }
+}
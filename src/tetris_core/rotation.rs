@@ -16,9 +16,7 @@ impl RotationSystem {
         let kick_offsets = Self::get_kick_offsets(piece.piece_type, piece.rotation, rotated_piece.rotation);
         
         for &(row_offset, col_offset) in kick_offsets.iter() {
-            let mut kicked_piece = rotated_piece.clone();
-            kicked_piece.row += row_offset;
-            kicked_piece.col += col_offset;
+            let kicked_piece = rotated_piece.translated(row_offset, col_offset);
             
             // If this position works, return it
             if board.can_place(&kicked_piece) {
@@ -40,9 +38,7 @@ impl RotationSystem {
         let kick_offsets = Self::get_kick_offsets(piece.piece_type, piece.rotation, rotated_piece.rotation);
         
         for &(row_offset, col_offset) in kick_offsets.iter() {
-            let mut kicked_piece = rotated_piece.clone();
-            kicked_piece.row += row_offset;
-            kicked_piece.col += col_offset;
+            let kicked_piece = rotated_piece.translated(row_offset, col_offset);
             
             // If this position works, return it
             if board.can_place(&kicked_piece) {
@@ -54,36 +50,41 @@ impl RotationSystem {
         None
     }
     
+    /// Gets the (row, col) kick offsets tested, in order, when rotating a piece between two orientations
+    pub fn kick_offsets(piece_type: PieceType, from: Rotation, to: Rotation) -> &'static [(i32, i32)] {
+        Self::get_kick_offsets(piece_type, from, to)
+    }
+    
     /// Gets the kick offsets for a rotation according to SRS
     fn get_kick_offsets(piece_type: PieceType, from: Rotation, to: Rotation) -> &'static [(i32, i32)] {
         // The Super Rotation System (SRS) kick offsets
         if piece_type == PieceType::I {
-            // I-piece has special kick data
+            // I-piece has special kick data, as (row, col) with rows counting down
             match (from, to) {
-                (Rotation::North, Rotation::East) => &[(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
-                (Rotation::East, Rotation::North) => &[(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
-                (Rotation::East, Rotation::South) => &[(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)],
-                (Rotation::South, Rotation::East) => &[(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
-                (Rotation::South, Rotation::West) => &[(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
-                (Rotation::West, Rotation::South) => &[(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
-                (Rotation::West, Rotation::North) => &[(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
-                (Rotation::North, Rotation::West) => &[(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)],
+                (Rotation::North, Rotation::East) => &[(0, 0), (0, -2), (0, 1), (1, -2), (-2, 1)],
+                (Rotation::East, Rotation::North) => &[(0, 0), (0, 2), (0, -1), (-1, 2), (2, -1)],
+                (Rotation::East, Rotation::South) => &[(0, 0), (0, -1), (0, 2), (-2, -1), (1, 2)],
+                (Rotation::South, Rotation::East) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
+                (Rotation::South, Rotation::West) => &[(0, 0), (0, 2), (0, -1), (-1, 2), (2, -1)],
+                (Rotation::West, Rotation::South) => &[(0, 0), (0, -2), (0, 1), (1, -2), (-2, 1)],
+                (Rotation::West, Rotation::North) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
+                (Rotation::North, Rotation::West) => &[(0, 0), (0, -1), (0, 2), (-2, -1), (1, 2)],
                 _ => &[(0, 0)], // Should never happen with valid rotations
             }
         } else if piece_type == PieceType::O {
             // O-piece doesn't rotate
             &[(0, 0)]
         } else {
-            // Standard kicks for J, L, S, T, Z pieces
+            // Standard kicks for J, L, S, T, Z pieces, also as (row, col) with rows counting down
             match (from, to) {
-                (Rotation::North, Rotation::East) => &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
-                (Rotation::East, Rotation::North) => &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
-                (Rotation::East, Rotation::South) => &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
-                (Rotation::South, Rotation::East) => &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
-                (Rotation::South, Rotation::West) => &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
-                (Rotation::West, Rotation::South) => &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
-                (Rotation::West, Rotation::North) => &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
-                (Rotation::North, Rotation::West) => &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+                (Rotation::North, Rotation::East) => &[(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
+                (Rotation::East, Rotation::North) => &[(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+                (Rotation::East, Rotation::South) => &[(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+                (Rotation::South, Rotation::East) => &[(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
+                (Rotation::South, Rotation::West) => &[(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
+                (Rotation::West, Rotation::South) => &[(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
+                (Rotation::West, Rotation::North) => &[(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
+                (Rotation::North, Rotation::West) => &[(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
                 _ => &[(0, 0)], // Should never happen with valid rotations
             }
         }
@@ -339,4 +340,65 @@ mod tests {
         let north_again = RotationSystem::rotate_clockwise(&west_piece, &board).unwrap();
         assert_eq!(north_again.rotation, Rotation::North);
     }
-}
\ No newline at end of file
+    
+    // Published SRS kick data, as (x, y) with y pointing up, in the order
+    // 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L
+    const TRANSITIONS: [(Rotation, Rotation); 8] = [
+        (Rotation::North, Rotation::East), (Rotation::East, Rotation::North),
+        (Rotation::East, Rotation::South), (Rotation::South, Rotation::East),
+        (Rotation::South, Rotation::West), (Rotation::West, Rotation::South),
+        (Rotation::West, Rotation::North), (Rotation::North, Rotation::West),
+    ];
+    
+    // Check the kick table of a piece against SRS data, where (x, y) is (row, col) = (-y, x) here
+    fn assert_matches_srs(piece_type: PieceType, srs: &[[(i32, i32); 5]; 8]) {
+        for (&(from, to), tests) in TRANSITIONS.iter().zip(srs) {
+            let expected: Vec<(i32, i32)> = tests.iter().map(|&(x, y)| (-y, x)).collect();
+            assert_eq!(RotationSystem::kick_offsets(piece_type, from, to), expected.as_slice(), "{:?} {:?} -> {:?}", piece_type, from, to);
+        }
+    }
+    
+    #[test]
+    fn test_i_kick_offsets_match_srs() {
+        assert_matches_srs(PieceType::I, &[
+            [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+            [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+            [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+            [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+            [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+            [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+        ]);
+        assert_eq!(RotationSystem::kick_offsets(PieceType::O, Rotation::North, Rotation::East), &[(0, 0)]);
+    }
+    
+    #[test]
+    fn test_jlstz_kick_offsets_match_srs() {
+        let srs = [
+            [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+            [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+            [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+            [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+        ];
+        for piece_type in [PieceType::J, PieceType::L, PieceType::S, PieceType::T, PieceType::Z] {
+            assert_matches_srs(piece_type, &srs);
+        }
+    }
+    
+    #[test]
+    fn test_i_piece_kicks_off_left_wall() {
+        let board = Board::new();
+        
+        // A vertical I against the left wall kicks two columns right to lie flat
+        let mut i_piece = Piece::new(PieceType::I, 10, -1);
+        i_piece.rotation = Rotation::East;
+        let rotated = RotationSystem::rotate_clockwise(&i_piece, &board).unwrap();
+        assert_eq!(rotated.rotation, Rotation::South);
+        assert_eq!((rotated.row, rotated.col), (10, 1));
+    }
+}
@@ -0,0 +1,74 @@
+/// Running statistics about how a game is being played
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameStats {
+    /// Pieces locked onto the board
+    pub pieces_placed: u32,
+    /// Placements that used more inputs than the finesse-optimal route
+    pub finesse_faults: u32,
+    /// Number of clears of each size: singles, doubles, triples and tetrises
+    pub clears: [u32; 4],
+    /// Garbage lines sent by clears, including lines that cancelled incoming garbage
+    pub attack_sent: u32,
+    /// Garbage lines queued against this player by opponents
+    pub garbage_received: u32,
+}
+
+impl GameStats {
+    /// Create empty statistics
+    pub fn new() -> Self {
+        GameStats {
+            pieces_placed: 0,
+            finesse_faults: 0,
+            clears: [0; 4],
+            attack_sent: 0,
+            garbage_received: 0,
+        }
+    }
+
+    /// Record a placement that cleared `lines` lines
+    pub fn record_clear(&mut self, lines: usize) {
+        if (1..=4).contains(&lines) {
+            self.clears[lines - 1] += 1;
+        }
+    }
+
+    /// Total lines cleared
+    pub fn lines_cleared(&self) -> u32 {
+        self.clears.iter()
+            .enumerate()
+            .map(|(index, &count)| (index as u32 + 1) * count)
+            .sum()
+    }
+
+    /// Fraction of cleared lines that came from tetrises (0.0 before any line is cleared)
+    pub fn tetris_rate(&self) -> f64 {
+        let lines = self.lines_cleared();
+        if lines == 0 {
+            return 0.0;
+        }
+        (self.clears[3] * 4) as f64 / lines as f64
+    }
+}
+
+impl Default for GameStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tetris_rate() {
+        let mut stats = GameStats::new();
+        assert_eq!(stats.tetris_rate(), 0.0);
+
+        stats.record_clear(4);
+        stats.record_clear(1);
+        stats.record_clear(0);
+        assert_eq!(stats.lines_cleared(), 5);
+        assert_eq!(stats.tetris_rate(), 4.0 / 5.0);
+    }
+}